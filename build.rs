@@ -7,6 +7,56 @@ fn main() {
             WM_ACTIVATE,
             WM_NCCALCSIZE,
             WM_NCHITTEST,
+            WM_NCPAINT,
+            WM_NCLBUTTONDOWN,
+            WM_SYSCOMMAND,
+            WM_SIZE,
+            WM_WINDOWPOSCHANGED,
+            WM_DISPLAYCHANGE,
+            WM_DPICHANGED,
+            WM_MOUSEMOVE,
+            WM_TIMER,
+            WM_GETMINMAXINFO,
+            WM_SIZING,
+            WM_MOVING,
+            WM_NCMOUSEMOVE,
+            WM_NCMOUSELEAVE,
+            WM_NCLBUTTONDBLCLK,
+            WM_LBUTTONDOWN,
+            WM_LBUTTONUP,
+            WM_LBUTTONDBLCLK,
+            WM_RBUTTONUP,
+            WM_MBUTTONUP,
+            WM_CAPTURECHANGED,
+            MK_LBUTTON,
+            WM_POINTERDOWN, WM_POINTERUPDATE,
+            WM_NCPOINTERDOWN, WM_NCPOINTERUPDATE,
+            HTMINBUTTON, HTMAXBUTTON, HTCLOSE,
+            TME_LEAVE, TME_NONCLIENT,
+            WMSZ_LEFT, WMSZ_RIGHT, WMSZ_TOP, WMSZ_TOPLEFT,
+            WMSZ_TOPRIGHT, WMSZ_BOTTOM, WMSZ_BOTTOMLEFT, WMSZ_BOTTOMRIGHT,
+            HTCLIENT,
+            SWP_NOOWNERZORDER,
+            SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER,
+            SM_CXDRAG, SM_CYDRAG,
+            SW_RESTORE,
+            SIZE_MAXIMIZED, SIZE_MINIMIZED,
+            WA_INACTIVE,
+            MONITOR_DEFAULTTONEAREST,
+            SC_MINIMIZE, SC_MAXIMIZE, SC_RESTORE, SC_CLOSE,
+            SC_MOVE, SC_SIZE, SC_KEYMENU,
+            MF_BYCOMMAND, MF_ENABLED, MF_GRAYED,
+            WM_NCRBUTTONUP,
+            WM_NCMBUTTONUP,
+            WM_GETOBJECT,
+            WM_GETTEXT,
+            WM_GETTEXTLENGTH,
+            WM_SETTEXT,
+            TPM_RETURNCMD, TPM_RIGHTBUTTON,
+            WM_NCACTIVATE,
+            WM_DWMCOMPOSITIONCHANGED,
+            COLOR_ACTIVEBORDER,
+            COLOR_INACTIVEBORDER,
             WS_CAPTION,
             WS_OVERLAPPEDWINDOW,
             SWP_FRAMECHANGED,
@@ -17,10 +67,51 @@ fn main() {
         }
         windows::win32::windows_and_messaging::{
             GetWindowRect,
+            GetClientRect,
+            GetForegroundWindow,
+            GetCursorPos,
+            GetSystemMetrics,
+            GetMessageExtraInfo,
+            PostMessageW,
+            SendMessageW,
+            IsZoomed,
+            IsIconic,
+            IsWindowVisible,
+            ShowWindow,
+            SetTimer,
+            KillTimer,
+            GetWindowPlacement,
+            SetWindowPlacement,
+            WINDOWPLACEMENT,
+            MINMAXINFO,
             AdjustWindowRectEx,
             SetWindowPos,
             NCCALCSIZE_PARAMS,
         }
+        windows::win32::gdi::{
+            GetWindowDC,
+            ReleaseDC,
+            ClientToScreen,
+            ScreenToClient,
+            ExcludeClipRect,
+            FillRect,
+            GetSysColorBrush,
+            MonitorFromWindow,
+            MonitorFromRect,
+            GetMonitorInfoW,
+            MONITORINFO,
+        }
+        windows::win32::keyboard_and_mouse_input::{
+            ReleaseCapture,
+            SetCapture,
+            TrackMouseEvent,
+            TRACKMOUSEEVENT,
+        }
+        windows::win32::menus_and_resources::{
+            GetSystemMenu,
+            TrackPopupMenu,
+            EnableMenuItem,
+        }
         windows::win32::shell::{
             SetWindowSubclass,
             RemoveWindowSubclass,
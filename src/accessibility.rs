@@ -0,0 +1,227 @@
+use crate::{
+    caption_buttons::{CaptionButton, CaptionButtons, CaptionGlyph},
+    command::WindowCommand,
+    hit_test::{Point, Rect},
+    layout::CaptionButtonRect,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessibleRole {
+    Window,
+    TitleBar,
+    Button,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibleNode {
+    pub role: AccessibleRole,
+    pub name: String,
+    /// Stays the same when the name changes, e.g. between maximize and restore.
+    pub automation_id: String,
+    /// In client coordinates.
+    pub bounds: Rect,
+    pub parent: Option<usize>,
+    /// What invoking the node does, for buttons.
+    pub command: Option<WindowCommand>,
+}
+
+/// A platform independent model of a custom title bar, for assistive technologies.
+///
+/// Nodes are referred to by their index, the window itself is always the first node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccessibilityTree {
+    pub nodes: Vec<AccessibleNode>,
+}
+impl AccessibilityTree {
+    pub const ROOT: usize = 0;
+
+    /// Builds the tree for a caption with the given title and buttons, all in client
+    /// coordinates.
+    pub fn new(
+        title: &str,
+        caption: Rect,
+        buttons: &[CaptionButtonRect],
+        button_states: &CaptionButtons,
+    ) -> Self {
+        let mut nodes = vec![
+            AccessibleNode {
+                role: AccessibleRole::Window,
+                name: title.to_owned(),
+                automation_id: String::new(),
+                bounds: caption,
+                parent: None,
+                command: None,
+            },
+            AccessibleNode {
+                role: AccessibleRole::TitleBar,
+                name: title.to_owned(),
+                automation_id: "TitleBar".to_owned(),
+                bounds: caption,
+                parent: Some(Self::ROOT),
+                command: None,
+            },
+        ];
+        for button in buttons {
+            let glyph = button_states.glyph(button.button);
+            nodes.push(AccessibleNode {
+                role: AccessibleRole::Button,
+                name: button_name(glyph).to_owned(),
+                automation_id: automation_id(button.button).to_owned(),
+                bounds: button.rect,
+                parent: Some(1),
+                command: Some(button_states.command(button.button)),
+            });
+        }
+        Self { nodes }
+    }
+    pub fn node(&self, id: usize) -> Option<&AccessibleNode> {
+        self.nodes.get(id)
+    }
+    pub fn parent(&self, id: usize) -> Option<usize> {
+        self.node(id)?.parent
+    }
+    pub fn children(&self, id: usize) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|child| self.nodes[*child].parent == Some(id))
+            .collect()
+    }
+    pub fn first_child(&self, id: usize) -> Option<usize> {
+        self.children(id).first().copied()
+    }
+    pub fn last_child(&self, id: usize) -> Option<usize> {
+        self.children(id).last().copied()
+    }
+    pub fn next_sibling(&self, id: usize) -> Option<usize> {
+        let siblings = self.children(self.parent(id)?);
+        let index = siblings.iter().position(|sibling| *sibling == id)?;
+        siblings.get(index + 1).copied()
+    }
+    pub fn previous_sibling(&self, id: usize) -> Option<usize> {
+        let siblings = self.children(self.parent(id)?);
+        let index = siblings.iter().position(|sibling| *sibling == id)?;
+        siblings.get(index.checked_sub(1)?).copied()
+    }
+    /// The innermost node below the root that contains `point`, in client coordinates.
+    pub fn node_at(&self, point: Point) -> Option<usize> {
+        let mut found = None;
+        let mut parent = Self::ROOT;
+        while let Some(child) = self
+            .children(parent)
+            .into_iter()
+            .find(|child| self.nodes[*child].bounds.contains(point))
+        {
+            found = Some(child);
+            parent = child;
+        }
+        found
+    }
+}
+
+fn button_name(glyph: CaptionGlyph) -> &'static str {
+    match glyph {
+        CaptionGlyph::Minimize => "Minimize",
+        CaptionGlyph::Maximize => "Maximize",
+        CaptionGlyph::Restore => "Restore",
+        CaptionGlyph::Close => "Close",
+    }
+}
+
+fn automation_id(button: CaptionButton) -> &'static str {
+    match button {
+        CaptionButton::Minimize => "Minimize",
+        CaptionButton::Maximize => "Maximize",
+        CaptionButton::Close => "Close",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, right: i32) -> Rect {
+        Rect {
+            left,
+            top: 0,
+            right,
+            bottom: 32,
+        }
+    }
+
+    fn tree(button_states: &CaptionButtons) -> AccessibilityTree {
+        let buttons = [
+            CaptionButtonRect {
+                button: CaptionButton::Minimize,
+                rect: rect(865, 910),
+            },
+            CaptionButtonRect {
+                button: CaptionButton::Maximize,
+                rect: rect(910, 955),
+            },
+            CaptionButtonRect {
+                button: CaptionButton::Close,
+                rect: rect(955, 1000),
+            },
+        ];
+        AccessibilityTree::new("Editor", rect(0, 1000), &buttons, button_states)
+    }
+
+    #[test]
+    fn buttons_sit_below_the_title_bar() {
+        let tree = tree(&CaptionButtons::new());
+        assert_eq!(tree.nodes.len(), 5);
+        assert_eq!(
+            tree.nodes[AccessibilityTree::ROOT].role,
+            AccessibleRole::Window
+        );
+        assert_eq!(tree.nodes[1].role, AccessibleRole::TitleBar);
+        assert_eq!(tree.nodes[1].name, "Editor");
+        assert_eq!(tree.children(AccessibilityTree::ROOT), vec![1]);
+        assert_eq!(tree.children(1), vec![2, 3, 4]);
+        assert_eq!(tree.parent(4), Some(1));
+        assert_eq!(tree.parent(AccessibilityTree::ROOT), None);
+    }
+
+    #[test]
+    fn buttons_are_named_after_what_they_do() {
+        let tree = tree(&CaptionButtons::new());
+        let names: Vec<_> = tree.nodes[2..]
+            .iter()
+            .map(|node| node.name.as_str())
+            .collect();
+        assert_eq!(names, ["Minimize", "Maximize", "Close"]);
+        assert_eq!(tree.nodes[3].command, Some(WindowCommand::Maximize));
+        assert_eq!(tree.nodes[4].command, Some(WindowCommand::Close));
+        assert_eq!(tree.nodes[1].command, None);
+    }
+
+    #[test]
+    fn maximized_windows_offer_restore_with_a_stable_id() {
+        let mut button_states = CaptionButtons::new();
+        button_states.set_maximized(true);
+        let tree = tree(&button_states);
+        assert_eq!(tree.nodes[3].name, "Restore");
+        assert_eq!(tree.nodes[3].automation_id, "Maximize");
+        assert_eq!(tree.nodes[3].command, Some(WindowCommand::Restore));
+    }
+
+    #[test]
+    fn siblings_navigate_in_order() {
+        let tree = tree(&CaptionButtons::new());
+        assert_eq!(tree.first_child(1), Some(2));
+        assert_eq!(tree.last_child(1), Some(4));
+        assert_eq!(tree.next_sibling(2), Some(3));
+        assert_eq!(tree.next_sibling(4), None);
+        assert_eq!(tree.previous_sibling(3), Some(2));
+        assert_eq!(tree.previous_sibling(2), None);
+        assert_eq!(tree.next_sibling(AccessibilityTree::ROOT), None);
+        assert_eq!(tree.first_child(4), None);
+    }
+
+    #[test]
+    fn node_at_finds_the_innermost_node() {
+        let tree = tree(&CaptionButtons::new());
+        assert_eq!(tree.node_at(Point { x: 999, y: 10 }), Some(4));
+        assert_eq!(tree.node_at(Point { x: 100, y: 10 }), Some(1));
+        assert_eq!(tree.node_at(Point { x: 100, y: 40 }), None);
+    }
+}
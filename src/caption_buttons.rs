@@ -0,0 +1,255 @@
+use crate::{
+    bindings::windows::win32::system_services::{HTCLOSE, HTMAXBUTTON, HTMINBUTTON},
+    command::WindowCommand,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionButton {
+    Minimize,
+    Maximize,
+    Close,
+}
+impl CaptionButton {
+    pub(crate) fn hit_test_code(&self) -> i32 {
+        match self {
+            Self::Minimize => HTMINBUTTON,
+            Self::Maximize => HTMAXBUTTON,
+            Self::Close => HTCLOSE,
+        }
+    }
+    pub(crate) fn from_hit_test_code(code: i32) -> Option<Self> {
+        match code {
+            _ if code == HTMINBUTTON => Some(Self::Minimize),
+            _ if code == HTMAXBUTTON => Some(Self::Maximize),
+            _ if code == HTCLOSE => Some(Self::Close),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonState {
+    Normal,
+    Hovered,
+    Pressed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionGlyph {
+    Minimize,
+    Maximize,
+    Restore,
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionButtonEvent {
+    /// The state of at least one button changed.
+    Redraw,
+    /// A button was clicked. The buttons have to be redrawn as well.
+    Invoke(WindowCommand),
+}
+
+/// Tracks hover and press states for custom drawn caption buttons.
+///
+/// A press only turns into a click when the pointer is released over the same button,
+/// moving off the button while it's held shows it as released again.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CaptionButtons {
+    hovered: Option<CaptionButton>,
+    pressed: Option<CaptionButton>,
+    maximized: bool,
+}
+impl CaptionButtons {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn state(&self, button: CaptionButton) -> ButtonState {
+        match (self.pressed, self.hovered) {
+            (Some(pressed), Some(hovered)) if pressed == button && hovered == button => {
+                ButtonState::Pressed
+            }
+            // Other buttons don't light up while one is held down.
+            (Some(_), _) => ButtonState::Normal,
+            (None, Some(hovered)) if hovered == button => ButtonState::Hovered,
+            _ => ButtonState::Normal,
+        }
+    }
+    pub fn glyph(&self, button: CaptionButton) -> CaptionGlyph {
+        match button {
+            CaptionButton::Minimize => CaptionGlyph::Minimize,
+            CaptionButton::Maximize if self.maximized => CaptionGlyph::Restore,
+            CaptionButton::Maximize => CaptionGlyph::Maximize,
+            CaptionButton::Close => CaptionGlyph::Close,
+        }
+    }
+    pub fn is_pressed(&self) -> bool {
+        self.pressed.is_some()
+    }
+    pub fn set_maximized(&mut self, maximized: bool) -> Option<CaptionButtonEvent> {
+        let changed = self.maximized != maximized;
+        self.maximized = maximized;
+        Self::redraw_if(changed)
+    }
+    pub fn pointer_moved(&mut self, over: Option<CaptionButton>) -> Option<CaptionButtonEvent> {
+        self.update(|buttons| buttons.hovered = over)
+    }
+    pub fn pointer_pressed(&mut self, over: Option<CaptionButton>) -> Option<CaptionButtonEvent> {
+        self.update(|buttons| {
+            buttons.hovered = over;
+            buttons.pressed = over;
+        })
+    }
+    pub fn pointer_released(&mut self, over: Option<CaptionButton>) -> Option<CaptionButtonEvent> {
+        let clicked = match self.pressed {
+            Some(pressed) if Some(pressed) == over => Some(pressed),
+            _ => None,
+        };
+        let redraw = self.update(|buttons| {
+            buttons.hovered = over;
+            buttons.pressed = None;
+        });
+        match clicked {
+            Some(button) => Some(CaptionButtonEvent::Invoke(self.command(button))),
+            None => redraw,
+        }
+    }
+    pub fn pointer_left(&mut self) -> Option<CaptionButtonEvent> {
+        self.update(|buttons| buttons.hovered = None)
+    }
+    pub fn cancel(&mut self) -> Option<CaptionButtonEvent> {
+        self.update(|buttons| {
+            buttons.hovered = None;
+            buttons.pressed = None;
+        })
+    }
+    pub(crate) fn command(&self, button: CaptionButton) -> WindowCommand {
+        match self.glyph(button) {
+            CaptionGlyph::Minimize => WindowCommand::Minimize,
+            CaptionGlyph::Maximize => WindowCommand::Maximize,
+            CaptionGlyph::Restore => WindowCommand::Restore,
+            CaptionGlyph::Close => WindowCommand::Close,
+        }
+    }
+    fn update(&mut self, update: impl FnOnce(&mut Self)) -> Option<CaptionButtonEvent> {
+        let before = *self;
+        update(self);
+        let changed = [
+            CaptionButton::Minimize,
+            CaptionButton::Maximize,
+            CaptionButton::Close,
+        ]
+        .iter()
+        .any(|button| before.state(*button) != self.state(*button));
+        Self::redraw_if(changed)
+    }
+    fn redraw_if(changed: bool) -> Option<CaptionButtonEvent> {
+        if changed {
+            Some(CaptionButtonEvent::Redraw)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hover_highlights_one_button() {
+        let mut buttons = CaptionButtons::new();
+        assert_eq!(
+            buttons.pointer_moved(Some(CaptionButton::Close)),
+            Some(CaptionButtonEvent::Redraw)
+        );
+        assert_eq!(buttons.state(CaptionButton::Close), ButtonState::Hovered);
+        assert_eq!(buttons.state(CaptionButton::Minimize), ButtonState::Normal);
+        assert_eq!(buttons.pointer_moved(Some(CaptionButton::Close)), None);
+        assert_eq!(buttons.pointer_left(), Some(CaptionButtonEvent::Redraw));
+        assert_eq!(buttons.state(CaptionButton::Close), ButtonState::Normal);
+    }
+
+    #[test]
+    fn press_and_release_on_the_same_button_invokes_it() {
+        let mut buttons = CaptionButtons::new();
+        buttons.pointer_moved(Some(CaptionButton::Minimize));
+        assert_eq!(
+            buttons.pointer_pressed(Some(CaptionButton::Minimize)),
+            Some(CaptionButtonEvent::Redraw)
+        );
+        assert!(buttons.is_pressed());
+        assert_eq!(buttons.state(CaptionButton::Minimize), ButtonState::Pressed);
+        assert_eq!(
+            buttons.pointer_released(Some(CaptionButton::Minimize)),
+            Some(CaptionButtonEvent::Invoke(WindowCommand::Minimize))
+        );
+        assert!(!buttons.is_pressed());
+        assert_eq!(buttons.state(CaptionButton::Minimize), ButtonState::Hovered);
+    }
+
+    #[test]
+    fn maximize_invokes_restore_while_maximized() {
+        let mut buttons = CaptionButtons::new();
+        assert_eq!(
+            buttons.set_maximized(true),
+            Some(CaptionButtonEvent::Redraw)
+        );
+        assert_eq!(
+            buttons.glyph(CaptionButton::Maximize),
+            CaptionGlyph::Restore
+        );
+        buttons.pointer_pressed(Some(CaptionButton::Maximize));
+        assert_eq!(
+            buttons.pointer_released(Some(CaptionButton::Maximize)),
+            Some(CaptionButtonEvent::Invoke(WindowCommand::Restore))
+        );
+    }
+
+    #[test]
+    fn moving_off_before_release_cancels_the_click() {
+        let mut buttons = CaptionButtons::new();
+        buttons.pointer_pressed(Some(CaptionButton::Close));
+        assert_eq!(
+            buttons.pointer_moved(None),
+            Some(CaptionButtonEvent::Redraw)
+        );
+        assert_eq!(buttons.state(CaptionButton::Close), ButtonState::Normal);
+        // Other buttons stay dark while the press is held.
+        assert_eq!(buttons.pointer_moved(Some(CaptionButton::Maximize)), None);
+        assert_eq!(buttons.state(CaptionButton::Maximize), ButtonState::Normal);
+        assert_eq!(
+            buttons.pointer_released(Some(CaptionButton::Maximize)),
+            Some(CaptionButtonEvent::Redraw)
+        );
+        assert_eq!(buttons.state(CaptionButton::Maximize), ButtonState::Hovered);
+    }
+
+    #[test]
+    fn moving_back_before_release_still_clicks() {
+        let mut buttons = CaptionButtons::new();
+        buttons.pointer_pressed(Some(CaptionButton::Close));
+        buttons.pointer_moved(None);
+        assert_eq!(
+            buttons.pointer_moved(Some(CaptionButton::Close)),
+            Some(CaptionButtonEvent::Redraw)
+        );
+        assert_eq!(
+            buttons.pointer_released(Some(CaptionButton::Close)),
+            Some(CaptionButtonEvent::Invoke(WindowCommand::Close))
+        );
+    }
+
+    #[test]
+    fn losing_capture_cancels_the_press() {
+        let mut buttons = CaptionButtons::new();
+        buttons.pointer_pressed(Some(CaptionButton::Close));
+        assert_eq!(buttons.cancel(), Some(CaptionButtonEvent::Redraw));
+        assert!(!buttons.is_pressed());
+        assert_eq!(buttons.state(CaptionButton::Close), ButtonState::Normal);
+        assert_eq!(
+            buttons.pointer_released(Some(CaptionButton::Close)),
+            Some(CaptionButtonEvent::Redraw)
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    bindings::windows::win32::{
+        menus_and_resources::{EnableMenuItem, GetSystemMenu, TrackPopupMenu, HMENU},
+        system_services::{
+            MF_BYCOMMAND, MF_ENABLED, MF_GRAYED, SC_CLOSE, SC_MAXIMIZE, SC_MINIMIZE, SC_MOVE,
+            SC_RESTORE, SC_SIZE, TPM_RETURNCMD, TPM_RIGHTBUTTON, WM_SYSCOMMAND,
+        },
+        windows_and_messaging::{IsZoomed, PostMessageW, SendMessageW, HWND, LPARAM, WPARAM},
+    },
+    hit_test::Point,
+    state::WindowState,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowCommand {
+    Minimize,
+    Maximize,
+    Restore,
+    Close,
+}
+impl WindowCommand {
+    fn w_param(&self) -> WPARAM {
+        match self {
+            Self::Minimize => WPARAM(SC_MINIMIZE as _),
+            Self::Maximize => WPARAM(SC_MAXIMIZE as _),
+            Self::Restore => WPARAM(SC_RESTORE as _),
+            Self::Close => WPARAM(SC_CLOSE as _),
+        }
+    }
+}
+
+pub(crate) unsafe fn execute(h_wnd: HWND, command: WindowCommand) {
+    // Going through WM_SYSCOMMAND rather than ShowWindow keeps the system animations.
+    SendMessageW(h_wnd, WM_SYSCOMMAND as _, command.w_param(), LPARAM(0));
+}
+
+// Runs the command once the current message has been handled.
+pub(crate) unsafe fn post(h_wnd: HWND, command: WindowCommand) {
+    PostMessageW(h_wnd, WM_SYSCOMMAND as _, command.w_param(), LPARAM(0));
+}
+
+pub(crate) unsafe fn toggle_maximize(h_wnd: HWND) {
+    if IsZoomed(h_wnd).as_bool() {
+        execute(h_wnd, WindowCommand::Restore);
+    } else {
+        execute(h_wnd, WindowCommand::Maximize);
+    }
+}
+
+// `state` is the state tracked by the subclass, so the menu agrees with the hit test.
+pub(crate) unsafe fn show_system_menu_at(h_wnd: HWND, position: Point, state: &WindowState) {
+    let menu = GetSystemMenu(h_wnd, false.into());
+    if menu.0 == 0 {
+        return;
+    }
+    sync_system_menu(menu, state);
+    let command = TrackPopupMenu(
+        menu,
+        (TPM_RETURNCMD | TPM_RIGHTBUTTON) as _,
+        position.x,
+        position.y,
+        0,
+        h_wnd,
+        std::ptr::null(),
+    );
+    if command.0 != 0 {
+        PostMessageW(h_wnd, WM_SYSCOMMAND as _, WPARAM(command.0 as _), LPARAM(0));
+    }
+}
+
+// The system keeps these up to date for the native caption only.
+unsafe fn sync_system_menu(menu: HMENU, state: &WindowState) {
+    let items = [
+        (SC_RESTORE, state.maximized || state.minimized),
+        (SC_MOVE, !state.maximized && !state.fullscreen),
+        (SC_SIZE, state.is_resizable()),
+        (SC_MINIMIZE, !state.minimized),
+        (SC_MAXIMIZE, !state.maximized && !state.fullscreen),
+    ];
+    for (command, enabled) in items.iter() {
+        let flag = if *enabled { MF_ENABLED } else { MF_GRAYED };
+        EnableMenuItem(menu, *command as _, (MF_BYCOMMAND | flag) as _);
+    }
+}
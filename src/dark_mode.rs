@@ -58,33 +58,36 @@ static WIN10_BUILD: Lazy<Option<u32>> = Lazy::new(|| {
     }
 });
 
+pub(crate) fn windows_10_build() -> Option<u32> {
+    *WIN10_BUILD
+}
+
 static DARK_MODE_SUPPORTED: Lazy<bool> = Lazy::new(|| match *WIN10_BUILD {
     Some(build) => build >= 17763,
     None => false,
 });
 
-pub fn dark_dwm_decorations(hwnd: HWND, enable_dark_mode: bool) -> bool {
-    #[allow(non_snake_case)]
-    type WINDOWCOMPOSITIONATTRIB = u32;
-    const WCA_USEDARKMODECOLORS: WINDOWCOMPOSITIONATTRIB = 26;
+#[allow(non_snake_case)]
+type WINDOWCOMPOSITIONATTRIB = u32;
+const WCA_USEDARKMODECOLORS: WINDOWCOMPOSITIONATTRIB = 26;
 
-    #[allow(non_snake_case)]
-    #[repr(C)]
-    struct WINDOWCOMPOSITIONATTRIBDATA {
-        Attrib: WINDOWCOMPOSITIONATTRIB,
-        pvData: *mut c_void,
-        cbData: usize,
-    }
+#[allow(non_snake_case)]
+#[repr(C)]
+struct WINDOWCOMPOSITIONATTRIBDATA {
+    Attrib: WINDOWCOMPOSITIONATTRIB,
+    pvData: *mut c_void,
+    cbData: usize,
+}
 
-    #[dll(user32)]
-    extern "system" {
-        #[allow(non_snake_case)]
-        fn SetWindowCompositionAttribute(
-            h_wnd: HWND,
-            data: *mut WINDOWCOMPOSITIONATTRIBDATA,
-        ) -> BOOL;
-    }
+#[dll(user32)]
+extern "system" {
+    #[allow(non_snake_case)]
+    fn SetWindowCompositionAttribute(h_wnd: HWND, data: *mut WINDOWCOMPOSITIONATTRIBDATA) -> BOOL;
+    #[allow(non_snake_case)]
+    fn GetWindowCompositionAttribute(h_wnd: HWND, data: *mut WINDOWCOMPOSITIONATTRIBDATA) -> BOOL;
+}
 
+pub fn dark_dwm_decorations(hwnd: HWND, enable_dark_mode: bool) -> bool {
     if *DARK_MODE_SUPPORTED && SetWindowCompositionAttribute::exists() {
         unsafe {
             let mut is_dark_mode_bigbool = BOOL::from(enable_dark_mode);
@@ -100,3 +103,24 @@ pub fn dark_dwm_decorations(hwnd: HWND, enable_dark_mode: bool) -> bool {
         false
     }
 }
+
+pub fn is_dark_dwm_decorations(hwnd: HWND) -> Option<bool> {
+    if *DARK_MODE_SUPPORTED && GetWindowCompositionAttribute::exists() {
+        unsafe {
+            let mut is_dark_mode_bigbool = BOOL::from(false);
+            let mut data = WINDOWCOMPOSITIONATTRIBDATA {
+                Attrib: WCA_USEDARKMODECOLORS,
+                pvData: &mut is_dark_mode_bigbool as *mut _ as _,
+                cbData: mem::size_of::<BOOL>(),
+            };
+
+            if GetWindowCompositionAttribute(hwnd, &mut data).is_ok() {
+                Some(is_dark_mode_bigbool.as_bool())
+            } else {
+                None
+            }
+        }
+    } else {
+        None
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    bindings::windows::win32::{
+        display_devices::{POINT, RECT},
+        gdi::ClientToScreen,
+        keyboard_and_mouse_input::ReleaseCapture,
+        system_services::{
+            HTCAPTION, SM_CXDRAG, SM_CYDRAG, SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER,
+            WM_NCLBUTTONDOWN,
+        },
+        windows_and_messaging::{
+            GetCursorPos, GetSystemMetrics, GetWindowRect, PostMessageW, SetWindowPos, HWND, WPARAM,
+        },
+    },
+    hit_test::{Border, Point, Size},
+};
+
+pub(crate) unsafe fn begin_drag_move(h_wnd: HWND) -> windows::Result<()> {
+    begin_non_client_drag(h_wnd, HTCAPTION)
+}
+
+pub(crate) unsafe fn continue_drag_move(
+    h_wnd: HWND,
+    offset: Point,
+    item_origin: Point,
+) -> windows::Result<()> {
+    let mut cursor = POINT::default();
+    GetCursorPos(&mut cursor).ok()?;
+
+    // The window is positioned by its outer corner, not by its client area.
+    let mut window = RECT::default();
+    GetWindowRect(h_wnd, &mut window).ok()?;
+    let mut client_origin = POINT::default();
+    ClientToScreen(h_wnd, &mut client_origin);
+    let client_left = client_origin.x - window.left;
+    let client_top = client_origin.y - window.top;
+
+    SetWindowPos(
+        h_wnd,
+        HWND(0),
+        cursor.x - offset.x - item_origin.x - client_left,
+        cursor.y - offset.y - item_origin.y - client_top,
+        0,
+        0,
+        (SWP_NOACTIVATE | SWP_NOSIZE | SWP_NOZORDER) as _,
+    )
+    .ok()?;
+    begin_non_client_drag(h_wnd, HTCAPTION)
+}
+
+pub(crate) unsafe fn begin_resize(h_wnd: HWND, border: Border) -> windows::Result<()> {
+    begin_non_client_drag(h_wnd, border as i32)
+}
+
+unsafe fn begin_non_client_drag(h_wnd: HWND, hit_test: i32) -> windows::Result<()> {
+    let mut cursor = POINT::default();
+    GetCursorPos(&mut cursor).ok()?;
+
+    // The window has the mouse captured while the button is held down, which would keep
+    // the system move/size loop from receiving mouse input.
+    ReleaseCapture();
+
+    // Pretend the button went down on the non-client area, so the default window
+    // procedure starts the move/size loop just like it would for the native frame.
+    let position = Point::from_win32(&cursor);
+    PostMessageW(
+        h_wnd,
+        WM_NCLBUTTONDOWN as _,
+        WPARAM(hit_test as _),
+        position.to_l_param(),
+    )
+    .ok()
+}
+
+// How far the mouse has to move with the button down before it counts as a drag.
+pub(crate) unsafe fn drag_threshold() -> Size {
+    Size {
+        width: GetSystemMetrics(SM_CXDRAG as _),
+        height: GetSystemMetrics(SM_CYDRAG as _),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CaptionPress {
+    /// The button went up, so it was just a click.
+    Released,
+    /// Still held within the threshold, it can become either.
+    Held,
+    /// Moved past the threshold with the button held, the window follows.
+    Dragged,
+}
+
+// What a press on the caption turned into by the time the mouse is at `position`.
+pub(crate) fn caption_press(
+    start: Point,
+    position: Point,
+    button_down: bool,
+    threshold: Size,
+) -> CaptionPress {
+    if !button_down {
+        CaptionPress::Released
+    } else if (position.x - start.x).abs() > threshold.width
+        || (position.y - start.y).abs() > threshold.height
+    {
+        CaptionPress::Dragged
+    } else {
+        CaptionPress::Held
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: Size = Size {
+        width: 4,
+        height: 4,
+    };
+
+    fn press(x: i32, y: i32, button_down: bool) -> CaptionPress {
+        caption_press(
+            Point { x: 100, y: 10 },
+            Point { x, y },
+            button_down,
+            THRESHOLD,
+        )
+    }
+
+    #[test]
+    fn moving_within_the_threshold_keeps_it_a_click() {
+        assert_eq!(press(100, 10, true), CaptionPress::Held);
+        assert_eq!(press(104, 6, true), CaptionPress::Held);
+        assert_eq!(press(96, 14, true), CaptionPress::Held);
+    }
+
+    #[test]
+    fn moving_past_the_threshold_on_either_axis_drags() {
+        assert_eq!(press(105, 10, true), CaptionPress::Dragged);
+        assert_eq!(press(95, 10, true), CaptionPress::Dragged);
+        assert_eq!(press(100, 15, true), CaptionPress::Dragged);
+        assert_eq!(press(100, 5, true), CaptionPress::Dragged);
+    }
+
+    #[test]
+    fn a_released_button_never_drags() {
+        assert_eq!(press(100, 10, false), CaptionPress::Released);
+        assert_eq!(press(200, 50, false), CaptionPress::Released);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::hit_test::{Point, Rect};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragOutDirection {
+    Any,
+    /// Only dragging above or below the strip detaches, like browser tabs.
+    Vertical,
+    Horizontal,
+}
+
+/// When a drag counts as leaving a tab strip or panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DragOut {
+    pub direction: DragOutDirection,
+    /// How far past the left or right edge of the strip the pointer has to go, in pixels.
+    pub horizontal_threshold: i32,
+    /// How far past the top or bottom edge of the strip the pointer has to go, in pixels.
+    pub vertical_threshold: i32,
+}
+impl Default for DragOut {
+    fn default() -> Self {
+        Self {
+            direction: DragOutDirection::Vertical,
+            horizontal_threshold: 0,
+            vertical_threshold: 30,
+        }
+    }
+}
+impl DragOut {
+    fn has_left(&self, strip: Rect, position: Point) -> bool {
+        let horizontal = position.x < strip.left - self.horizontal_threshold
+            || position.x >= strip.right + self.horizontal_threshold;
+        let vertical = position.y < strip.top - self.vertical_threshold
+            || position.y >= strip.bottom + self.vertical_threshold;
+        match self.direction {
+            DragOutDirection::Any => horizontal || vertical,
+            DragOutDirection::Vertical => vertical,
+            DragOutDirection::Horizontal => horizontal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DragOutEvent {
+    /// Where the pointer was when the drag left the strip.
+    pub position: Point,
+    /// Where the pointer was relative to the grabbed item when it was pressed.
+    ///
+    /// Pass this to `continue_drag_move` on the new window, along with where the item ends up
+    /// in it, to keep the item under the pointer.
+    pub offset: Point,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DragOutState {
+    Idle,
+    Pressed { strip: Rect, offset: Point },
+    Detached,
+}
+
+/// Follows a drag that started in a tab strip and reports when it leaves the strip.
+///
+/// All positions are in the same coordinate space, usually screen coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DragOutTracker {
+    config: DragOut,
+    state: DragOutState,
+}
+impl DragOutTracker {
+    pub fn new(config: DragOut) -> Self {
+        Self {
+            config,
+            state: DragOutState::Idle,
+        }
+    }
+    /// Starts tracking a press at `position` on an item whose top left corner is at `origin`.
+    pub fn pointer_pressed(&mut self, position: Point, origin: Point, strip: Rect) {
+        self.state = DragOutState::Pressed {
+            strip,
+            offset: Point {
+                x: position.x - origin.x,
+                y: position.y - origin.y,
+            },
+        };
+    }
+    /// Returns an event the first time the pointer leaves the strip during a drag.
+    pub fn pointer_moved(&mut self, position: Point) -> Option<DragOutEvent> {
+        match self.state {
+            DragOutState::Pressed { strip, offset } if self.config.has_left(strip, position) => {
+                self.state = DragOutState::Detached;
+                Some(DragOutEvent { position, offset })
+            }
+            _ => None,
+        }
+    }
+    pub fn pointer_released(&mut self) {
+        self.state = DragOutState::Idle;
+    }
+    pub fn is_dragging(&self) -> bool {
+        matches!(self.state, DragOutState::Pressed { .. })
+    }
+    pub fn is_detached(&self) -> bool {
+        self.state == DragOutState::Detached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRIP: Rect = Rect {
+        left: 100,
+        top: 50,
+        right: 500,
+        bottom: 80,
+    };
+
+    fn point(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    fn pressed(direction: DragOutDirection) -> DragOutTracker {
+        let mut tracker = DragOutTracker::new(DragOut {
+            direction,
+            horizontal_threshold: 20,
+            vertical_threshold: 30,
+        });
+        tracker.pointer_pressed(point(150, 60), point(120, 50), STRIP);
+        tracker
+    }
+
+    fn leaves(direction: DragOutDirection, position: Point) -> bool {
+        pressed(direction).pointer_moved(position).is_some()
+    }
+
+    #[test]
+    fn vertical_thresholds() {
+        let direction = DragOutDirection::Vertical;
+        assert!(!leaves(direction, point(150, 20)));
+        assert!(leaves(direction, point(150, 19)));
+        assert!(!leaves(direction, point(150, 109)));
+        assert!(leaves(direction, point(150, 110)));
+        assert!(!leaves(direction, point(0, 60)));
+        assert!(!leaves(direction, point(900, 60)));
+    }
+
+    #[test]
+    fn horizontal_thresholds() {
+        let direction = DragOutDirection::Horizontal;
+        assert!(!leaves(direction, point(80, 60)));
+        assert!(leaves(direction, point(79, 60)));
+        assert!(!leaves(direction, point(519, 60)));
+        assert!(leaves(direction, point(520, 60)));
+        assert!(!leaves(direction, point(150, 0)));
+        assert!(!leaves(direction, point(150, 200)));
+    }
+
+    #[test]
+    fn any_direction() {
+        let direction = DragOutDirection::Any;
+        assert!(!leaves(direction, point(80, 20)));
+        assert!(leaves(direction, point(79, 60)));
+        assert!(leaves(direction, point(520, 60)));
+        assert!(leaves(direction, point(150, 19)));
+        assert!(leaves(direction, point(150, 110)));
+    }
+
+    #[test]
+    fn reports_leaving_once_with_the_press_offset() {
+        let mut tracker = pressed(DragOutDirection::Vertical);
+        assert!(tracker.is_dragging());
+        assert_eq!(tracker.pointer_moved(point(200, 70)), None);
+        assert_eq!(
+            tracker.pointer_moved(point(200, 200)),
+            Some(DragOutEvent {
+                position: point(200, 200),
+                offset: point(30, 10),
+            })
+        );
+        assert!(tracker.is_detached());
+        assert!(!tracker.is_dragging());
+        assert_eq!(tracker.pointer_moved(point(200, 300)), None);
+
+        tracker.pointer_released();
+        assert!(!tracker.is_detached());
+    }
+
+    #[test]
+    fn moves_without_a_press_are_ignored() {
+        let mut tracker = DragOutTracker::new(DragOut::default());
+        assert_eq!(tracker.pointer_moved(point(0, 1000)), None);
+
+        tracker.pointer_pressed(point(150, 60), point(120, 50), STRIP);
+        tracker.pointer_released();
+        assert_eq!(tracker.pointer_moved(point(0, 1000)), None);
+        assert!(!tracker.is_dragging());
+    }
+}
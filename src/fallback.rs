@@ -0,0 +1,47 @@
+use crate::bindings::windows::win32::{
+    display_devices::{POINT, RECT},
+    gdi::{ClientToScreen, ExcludeClipRect, FillRect, GetSysColorBrush, GetWindowDC, ReleaseDC},
+    system_services::{COLOR_ACTIVEBORDER, COLOR_INACTIVEBORDER},
+    windows_and_messaging::{GetClientRect, GetWindowRect, HWND},
+};
+
+// Paints a flat border into the non-client area when there is no DWM frame to show.
+pub(crate) unsafe fn paint_non_client_frame(h_wnd: HWND, active: bool) {
+    let mut window = RECT::default();
+    GetWindowRect(h_wnd, &mut window);
+
+    // Get the client rectangle in window coordinates.
+    let mut client = RECT::default();
+    GetClientRect(h_wnd, &mut client);
+    let mut client_origin = POINT::default();
+    ClientToScreen(h_wnd, &mut client_origin);
+    let client_left = client_origin.x - window.left;
+    let client_top = client_origin.y - window.top;
+
+    let hdc = GetWindowDC(h_wnd);
+    if hdc.0 == 0 {
+        return;
+    }
+    ExcludeClipRect(
+        hdc,
+        client_left,
+        client_top,
+        client_left + client.right,
+        client_top + client.bottom,
+    );
+
+    let frame = RECT {
+        left: 0,
+        top: 0,
+        right: window.right - window.left,
+        bottom: window.bottom - window.top,
+    };
+    let color = if active {
+        COLOR_ACTIVEBORDER
+    } else {
+        COLOR_INACTIVEBORDER
+    };
+    FillRect(hdc, &frame, GetSysColorBrush(color as _));
+
+    ReleaseDC(h_wnd, hdc);
+}
@@ -0,0 +1,46 @@
+use crate::{
+    bindings::windows::win32::{
+        system_services::{SWP_FRAMECHANGED, SWP_NOOWNERZORDER, SW_RESTORE},
+        windows_and_messaging::{
+            GetWindowPlacement, IsZoomed, SetWindowPlacement, SetWindowPos, ShowWindow, HWND,
+            WINDOWPLACEMENT,
+        },
+    },
+    util::monitor_info,
+};
+
+pub(crate) unsafe fn save_placement(h_wnd: HWND) -> windows::Result<WINDOWPLACEMENT> {
+    let mut placement = WINDOWPLACEMENT {
+        length: std::mem::size_of::<WINDOWPLACEMENT>() as _,
+        ..Default::default()
+    };
+    GetWindowPlacement(h_wnd, &mut placement).ok()?;
+    Ok(placement)
+}
+
+pub(crate) unsafe fn restore_placement(h_wnd: HWND, placement: &WINDOWPLACEMENT) {
+    SetWindowPlacement(h_wnd, placement);
+}
+
+pub(crate) unsafe fn cover_monitor(h_wnd: HWND) {
+    // A maximized window would snap back to the work area.
+    if IsZoomed(h_wnd).as_bool() {
+        ShowWindow(h_wnd, SW_RESTORE as _);
+    }
+    fit_to_monitor(h_wnd);
+}
+
+pub(crate) unsafe fn fit_to_monitor(h_wnd: HWND) {
+    if let Some(monitor_info) = monitor_info(h_wnd) {
+        let screen = monitor_info.rc_monitor;
+        SetWindowPos(
+            h_wnd,
+            HWND(0),
+            screen.left,
+            screen.top,
+            screen.right - screen.left,
+            screen.bottom - screen.top,
+            (SWP_FRAMECHANGED | SWP_NOOWNERZORDER) as _,
+        );
+    }
+}
@@ -0,0 +1,564 @@
+use crate::{
+    caption_buttons::{ButtonState, CaptionGlyph},
+    dark_mode::Theme,
+    hit_test::Rect,
+};
+
+// Glyphs are drawn in a 10x10 box at 96 DPI, like the system caption buttons.
+const GLYPH_SIZE: f32 = 10.0;
+const SUBSAMPLES: usize = 4;
+
+const CLOSE_HOVERED_BACKGROUND: u32 = 0xffe8_1123;
+const CLOSE_PRESSED_BACKGROUND: u32 = 0xfff1_707a;
+const CLOSE_FOREGROUND: u32 = 0xffff_ffff;
+
+/// How the channels of a `u32` pixel are laid out in a buffer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// `0xAARRGGBB`, as used by minifb and softbuffer.
+    #[default]
+    Argb,
+    /// `0xRRGGBBAA`.
+    Rgba,
+}
+impl PixelFormat {
+    fn to_argb(self, pixel: u32) -> u32 {
+        match self {
+            Self::Argb => pixel,
+            Self::Rgba => pixel.rotate_right(8),
+        }
+    }
+    fn from_argb(self, pixel: u32) -> u32 {
+        match self {
+            Self::Argb => pixel,
+            Self::Rgba => pixel.rotate_left(8),
+        }
+    }
+}
+
+/// Colors in `0xAARRGGBB` format, whatever the format of the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GlyphColors {
+    pub foreground: u32,
+    pub hovered_background: u32,
+    pub pressed_background: u32,
+}
+impl GlyphColors {
+    pub fn for_theme(theme: &Theme) -> Self {
+        match theme {
+            Theme::Light => Self {
+                foreground: 0xff00_0000,
+                hovered_background: 0x1a00_0000,
+                pressed_background: 0x3300_0000,
+            },
+            Theme::Dark => Self {
+                foreground: 0xffff_ffff,
+                hovered_background: 0x1aff_ffff,
+                pressed_background: 0x33ff_ffff,
+            },
+        }
+    }
+}
+
+/// Paints a caption button, background and glyph, into `rect` of a pixel buffer with one
+/// `u32` per pixel.
+pub fn draw_caption_button(
+    buffer: &mut [u32],
+    buffer_width: usize,
+    format: PixelFormat,
+    rect: Rect,
+    glyph: CaptionGlyph,
+    state: ButtonState,
+    scale: f32,
+    colors: &GlyphColors,
+) {
+    let close = glyph == CaptionGlyph::Close;
+    let (background, foreground) = match state {
+        ButtonState::Normal => (None, colors.foreground),
+        ButtonState::Hovered if close => (Some(CLOSE_HOVERED_BACKGROUND), CLOSE_FOREGROUND),
+        ButtonState::Pressed if close => (Some(CLOSE_PRESSED_BACKGROUND), CLOSE_FOREGROUND),
+        ButtonState::Hovered => (Some(colors.hovered_background), colors.foreground),
+        ButtonState::Pressed => (Some(colors.pressed_background), colors.foreground),
+    };
+    if let Some(background) = background {
+        fill_rect(buffer, buffer_width, format, rect, background);
+    }
+    draw_glyph(buffer, buffer_width, format, rect, glyph, scale, foreground);
+}
+
+/// Paints just the glyph, centered in `rect` of a pixel buffer.
+pub fn draw_glyph(
+    buffer: &mut [u32],
+    buffer_width: usize,
+    format: PixelFormat,
+    rect: Rect,
+    glyph: CaptionGlyph,
+    scale: f32,
+    color: u32,
+) {
+    let size = (GLYPH_SIZE * scale).round();
+    let stroke = scale.round().max(1.0);
+    // Keep the glyph on whole pixels so straight edges stay crisp.
+    let origin_x = ((rect.left + rect.right) as f32 - size) / 2.0;
+    let origin_y = ((rect.top + rect.bottom) as f32 - size) / 2.0;
+    let origin = (origin_x.floor(), origin_y.floor());
+    let unit = size / GLYPH_SIZE;
+
+    let shapes = glyph_shapes(glyph, stroke / unit);
+    let bounds = Rect {
+        left: origin.0 as i32 - 1,
+        top: origin.1 as i32 - 1,
+        right: (origin.0 + size) as i32 + 1,
+        bottom: (origin.1 + size) as i32 + 1,
+    };
+    for_each_pixel(buffer, buffer_width, format, bounds, |x, y, pixel| {
+        let mut covered = 0;
+        for sample_y in 0..SUBSAMPLES {
+            for sample_x in 0..SUBSAMPLES {
+                let point = (
+                    (x as f32 + (sample_x as f32 + 0.5) / SUBSAMPLES as f32 - origin.0) / unit,
+                    (y as f32 + (sample_y as f32 + 0.5) / SUBSAMPLES as f32 - origin.1) / unit,
+                );
+                if shapes
+                    .iter()
+                    .any(|shape| shape.contains(point, stroke / unit, 1.0 / unit))
+                {
+                    covered += 1;
+                }
+            }
+        }
+        if covered > 0 {
+            let coverage = covered as f32 / (SUBSAMPLES * SUBSAMPLES) as f32;
+            *pixel = blend(*pixel, color, coverage);
+        }
+    });
+}
+
+pub(crate) fn fill_rect(
+    buffer: &mut [u32],
+    buffer_width: usize,
+    format: PixelFormat,
+    rect: Rect,
+    color: u32,
+) {
+    for_each_pixel(buffer, buffer_width, format, rect, |_, _, pixel| {
+        *pixel = blend(*pixel, color, 1.0);
+    });
+}
+
+// `paint` always sees the pixels as `0xAARRGGBB`.
+fn for_each_pixel(
+    buffer: &mut [u32],
+    buffer_width: usize,
+    format: PixelFormat,
+    rect: Rect,
+    mut paint: impl FnMut(i32, i32, &mut u32),
+) {
+    if buffer_width == 0 {
+        return;
+    }
+    let buffer_height = (buffer.len() / buffer_width) as i32;
+    let left = rect.left.max(0);
+    let top = rect.top.max(0);
+    let right = rect.right.min(buffer_width as i32);
+    let bottom = rect.bottom.min(buffer_height);
+    for y in top..bottom {
+        for x in left..right {
+            let pixel = &mut buffer[y as usize * buffer_width + x as usize];
+            let mut argb = format.to_argb(*pixel);
+            paint(x, y, &mut argb);
+            *pixel = format.from_argb(argb);
+        }
+    }
+}
+
+// Blends `color` over `pixel`, using the color's alpha scaled by `coverage`.
+pub(crate) fn blend(pixel: u32, color: u32, coverage: f32) -> u32 {
+    let alpha = ((color >> 24) & 0xff) as f32 / 255.0 * coverage;
+    let channel = |shift: u32, above: u32| {
+        let below = ((pixel >> shift) & 0xff) as f32;
+        ((above as f32 * alpha + below * (1.0 - alpha)).round() as u32).min(0xff) << shift
+    };
+    channel(24, 0xff)
+        | channel(16, (color >> 16) & 0xff)
+        | channel(8, (color >> 8) & 0xff)
+        | channel(0, color & 0xff)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    // Filled rectangle: left, top, right, bottom.
+    Fill(f32, f32, f32, f32),
+    // Rectangle outline, leaving out whatever lies inside the second rectangle.
+    Outline((f32, f32, f32, f32), Option<(f32, f32, f32, f32)>),
+    // Straight line, made of square stamps.
+    Line((f32, f32), (f32, f32)),
+}
+impl Shape {
+    // `point`, `stroke` and `pixel`, the size of one pixel, are in glyph units.
+    fn contains(&self, (x, y): (f32, f32), stroke: f32, pixel: f32) -> bool {
+        let inside = |(left, top, right, bottom): (f32, f32, f32, f32)| {
+            x >= left && x < right && y >= top && y < bottom
+        };
+        match *self {
+            // Thin fills still get at least one stroke of height.
+            Shape::Fill(left, top, right, bottom) => {
+                inside((left, top, right, bottom.max(top + stroke)))
+            }
+            Shape::Outline(outer, hidden) => {
+                let (left, top, right, bottom) = outer;
+                let inner = (left + stroke, top + stroke, right - stroke, bottom - stroke);
+                inside(outer) && !inside(inner) && !hidden.map(inside).unwrap_or(false)
+            }
+            Shape::Line((x0, y0), (x1, y1)) => {
+                // Stamped as a square of one stroke at every pixel along the longer axis,
+                // like an aliased line, so one pixel strokes cover whole pixels.
+                let (dx, dy) = (x1 - x0, y1 - y0);
+                let steps = (dx.abs().max(dy.abs()) / pixel).round().max(1.0);
+                let (step_x, step_y) = (dx / steps, dy / steps);
+                let (along, step) = if dx.abs() >= dy.abs() {
+                    (x - x0, step_x)
+                } else {
+                    (y - y0, step_y)
+                };
+                let reach = (stroke / 2.0 / pixel).ceil();
+                let nearest = (along / step).round();
+                let first = (nearest - reach).max(0.0) as i32;
+                let last = (nearest + reach).min(steps) as i32;
+                (first..=last).any(|i| {
+                    let (center_x, center_y) = (x0 + step_x * i as f32, y0 + step_y * i as f32);
+                    (x - center_x).abs() < stroke / 2.0 && (y - center_y).abs() < stroke / 2.0
+                })
+            }
+        }
+    }
+}
+
+// `stroke` is in glyph units.
+fn glyph_shapes(glyph: CaptionGlyph, stroke: f32) -> Vec<Shape> {
+    // Ends of diagonals sit half a stroke in, so odd strokes run through pixel centers.
+    let (near, far) = (stroke / 2.0, GLYPH_SIZE - stroke / 2.0);
+    match glyph {
+        CaptionGlyph::Minimize => vec![Shape::Fill(0.0, 5.0, 10.0, 6.0)],
+        CaptionGlyph::Maximize => vec![Shape::Outline((0.0, 0.0, 10.0, 10.0), None)],
+        CaptionGlyph::Restore => vec![
+            Shape::Outline((0.0, 2.0, 8.0, 10.0), None),
+            // The window behind only peeks out above and to the right.
+            Shape::Outline((2.0, 0.0, 10.0, 8.0), Some((0.0, 2.0, 8.0, 10.0))),
+        ],
+        CaptionGlyph::Close => vec![
+            Shape::Line((near, near), (far, far)),
+            Shape::Line((far, near), (near, far)),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Draws white on transparent and shows full coverage as `#`, partial as `+`.
+    fn render(glyph: CaptionGlyph, size: i32, scale: f32) -> Vec<String> {
+        let mut buffer = vec![0; (size * size) as usize];
+        let rect = Rect {
+            left: 0,
+            top: 0,
+            right: size,
+            bottom: size,
+        };
+        draw_glyph(
+            &mut buffer,
+            size as usize,
+            PixelFormat::Argb,
+            rect,
+            glyph,
+            scale,
+            0xffff_ffff,
+        );
+        buffer
+            .chunks(size as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|pixel| match pixel >> 24 {
+                        0 => '.',
+                        0xff => '#',
+                        _ => '+',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn minimize_at_100_percent() {
+        assert_eq!(
+            render(CaptionGlyph::Minimize, 12, 1.0),
+            [
+                "............",
+                "............",
+                "............",
+                "............",
+                "............",
+                "............",
+                ".##########.",
+                "............",
+                "............",
+                "............",
+                "............",
+                "............",
+            ]
+        );
+    }
+
+    #[test]
+    fn maximize_at_100_percent() {
+        assert_eq!(
+            render(CaptionGlyph::Maximize, 12, 1.0),
+            [
+                "............",
+                ".##########.",
+                ".#........#.",
+                ".#........#.",
+                ".#........#.",
+                ".#........#.",
+                ".#........#.",
+                ".#........#.",
+                ".#........#.",
+                ".#........#.",
+                ".##########.",
+                "............",
+            ]
+        );
+    }
+
+    #[test]
+    fn restore_at_100_percent() {
+        assert_eq!(
+            render(CaptionGlyph::Restore, 12, 1.0),
+            [
+                "............",
+                "...########.",
+                "...#......#.",
+                ".########.#.",
+                ".#......#.#.",
+                ".#......#.#.",
+                ".#......#.#.",
+                ".#......#.#.",
+                ".#......###.",
+                ".#......#...",
+                ".########...",
+                "............",
+            ]
+        );
+    }
+
+    #[test]
+    fn close_at_100_percent() {
+        assert_eq!(
+            render(CaptionGlyph::Close, 12, 1.0),
+            [
+                "............",
+                ".#........#.",
+                "..#......#..",
+                "...#....#...",
+                "....#..#....",
+                ".....##.....",
+                ".....##.....",
+                "....#..#....",
+                "...#....#...",
+                "..#......#..",
+                ".#........#.",
+                "............",
+            ]
+        );
+    }
+
+    #[test]
+    fn maximize_at_200_percent() {
+        assert_eq!(
+            render(CaptionGlyph::Maximize, 22, 2.0),
+            [
+                "......................",
+                ".####################.",
+                ".####################.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".##................##.",
+                ".####################.",
+                ".####################.",
+                "......................",
+            ]
+        );
+    }
+
+    #[test]
+    fn close_at_200_percent() {
+        assert_eq!(
+            render(CaptionGlyph::Close, 22, 2.0),
+            [
+                "......................",
+                ".##................##.",
+                ".###..............###.",
+                "..###............###..",
+                "...###..........###...",
+                "....###........###....",
+                ".....###......###.....",
+                "......###....###......",
+                ".......###..###.......",
+                "........######........",
+                ".........####.........",
+                ".........####.........",
+                "........######........",
+                ".......###..###.......",
+                "......###....###......",
+                ".....###......###.....",
+                "....###........###....",
+                "...###..........###...",
+                "..###............###..",
+                ".###..............###.",
+                ".##................##.",
+                "......................",
+            ]
+        );
+    }
+
+    #[test]
+    fn restore_at_150_percent() {
+        assert_eq!(
+            render(CaptionGlyph::Restore, 16, 1.5),
+            [
+                "...############.",
+                "...############.",
+                "...##........##.",
+                "############.##.",
+                "############.##.",
+                "##........##.##.",
+                "##........##.##.",
+                "##........##.##.",
+                "##........##.##.",
+                "##........##.##.",
+                "##........#####.",
+                "##........#####.",
+                "##........##....",
+                "############....",
+                "############....",
+                "................",
+            ]
+        );
+    }
+
+    #[test]
+    fn close_button_turns_red_when_hovered() {
+        let rect = Rect {
+            left: 0,
+            top: 0,
+            right: 46,
+            bottom: 32,
+        };
+        let mut buffer = vec![0xff20_2020; 46 * 32];
+        let colors = GlyphColors::for_theme(&Theme::Dark);
+        draw_caption_button(
+            &mut buffer,
+            46,
+            PixelFormat::Argb,
+            rect,
+            CaptionGlyph::Close,
+            ButtonState::Hovered,
+            1.0,
+            &colors,
+        );
+        assert_eq!(buffer[0], CLOSE_HOVERED_BACKGROUND);
+
+        let mut buffer = vec![0xff20_2020; 46 * 32];
+        draw_caption_button(
+            &mut buffer,
+            46,
+            PixelFormat::Argb,
+            rect,
+            CaptionGlyph::Maximize,
+            ButtonState::Normal,
+            1.0,
+            &colors,
+        );
+        assert_eq!(buffer[0], 0xff20_2020);
+    }
+
+    #[test]
+    fn rgba_buffers_get_the_same_pixels() {
+        let rect = Rect {
+            left: 0,
+            top: 0,
+            right: 12,
+            bottom: 12,
+        };
+        let mut argb = vec![0xff20_4060; 12 * 12];
+        let mut rgba = vec![0x2040_60ff; 12 * 12];
+        for (buffer, format) in [
+            (&mut argb, PixelFormat::Argb),
+            (&mut rgba, PixelFormat::Rgba),
+        ] {
+            draw_caption_button(
+                buffer,
+                12,
+                format,
+                rect,
+                CaptionGlyph::Close,
+                ButtonState::Hovered,
+                1.0,
+                &GlyphColors::for_theme(&Theme::Dark),
+            );
+        }
+        assert_eq!(argb[0], CLOSE_HOVERED_BACKGROUND);
+        assert_eq!(rgba[0], 0xe811_23ff);
+        assert_eq!(argb[1 + 12], CLOSE_FOREGROUND);
+        assert_eq!(rgba[1 + 12], 0xffff_ffff);
+        assert!(argb
+            .iter()
+            .zip(&rgba)
+            .all(|(argb, rgba)| argb.rotate_left(8) == *rgba));
+    }
+
+    #[test]
+    fn blend_composites_over_the_pixel() {
+        assert_eq!(blend(0xff00_0000, 0xffff_ffff, 1.0), 0xffff_ffff);
+        assert_eq!(blend(0xff00_0000, 0xffff_ffff, 0.0), 0xff00_0000);
+        assert_eq!(blend(0xff00_0000, 0x80ff_ffff, 1.0), 0xff80_8080);
+        // Transparent pixels pick up the coverage as alpha.
+        assert_eq!(blend(0x0000_0000, 0xffff_ffff, 0.5), 0x8080_8080);
+    }
+
+    #[test]
+    fn drawing_clips_to_the_buffer() {
+        let mut buffer = vec![0; 4 * 4];
+        let rect = Rect {
+            left: -20,
+            top: -20,
+            right: 40,
+            bottom: 40,
+        };
+        fill_rect(&mut buffer, 4, PixelFormat::Argb, rect, 0xffff_ffff);
+        draw_glyph(
+            &mut buffer,
+            4,
+            PixelFormat::Argb,
+            rect,
+            CaptionGlyph::Close,
+            3.0,
+            0xff00_0000,
+        );
+        assert!(buffer.iter().all(|pixel| pixel >> 24 == 0xff));
+    }
+}
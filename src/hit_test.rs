@@ -1,16 +1,21 @@
 use crate::{
     bindings::windows::win32::{
-        display_devices::RECT,
+        display_devices::{POINT, RECT},
         system_services::{
             HTBOTTOM, HTBOTTOMLEFT, HTBOTTOMRIGHT, HTCAPTION, HTLEFT, HTNOWHERE, HTRIGHT, HTTOP,
-            HTTOPLEFT, HTTOPRIGHT, LRESULT,
+            HTTOPLEFT, HTTOPRIGHT, LRESULT, WMSZ_BOTTOM, WMSZ_BOTTOMLEFT, WMSZ_BOTTOMRIGHT,
+            WMSZ_LEFT, WMSZ_RIGHT, WMSZ_TOP, WMSZ_TOPLEFT, WMSZ_TOPRIGHT,
         },
-        windows_and_messaging::{GetWindowRect, HWND, LPARAM},
+        windows_and_messaging::{GetWindowRect, HWND, LPARAM, WPARAM},
     },
-    window_frame_borders, WindowFrame,
+    caption_buttons::CaptionButton,
+    layout::CaptionButtonRect,
+    state::WindowState,
+    tab_strip::{TabStrip, TabStripArea},
+    window_frame_borders, Margins, WindowFrame,
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Point {
     pub x: i32,
     pub y: i32,
@@ -20,13 +25,64 @@ impl Point {
         let (x, y) = get_l_param_point(l_param);
         Self { x, y }
     }
+    pub(crate) fn to_l_param(&self) -> LPARAM {
+        LPARAM(make_l_param(self.x as u16, self.y as u16) as isize)
+    }
+    pub(crate) fn from_win32(point: &POINT) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+        }
+    }
+    pub(crate) fn to_win32(&self) -> POINT {
+        POINT {
+            x: self.x,
+            y: self.y,
+        }
+    }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Size {
     pub width: i32,
     pub height: i32,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+impl Rect {
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+    pub fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.left && point.x < self.right && point.y >= self.top && point.y < self.bottom
+    }
+    pub(crate) fn from_win32(rect: &RECT) -> Self {
+        Self {
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+        }
+    }
+    pub(crate) fn to_win32(&self) -> RECT {
+        RECT {
+            left: self.left,
+            top: self.top,
+            right: self.right,
+            bottom: self.bottom,
+        }
+    }
+}
+
 pub(crate) struct HitTest {
     pub(crate) area: HitTestArea,
     pub(crate) client_position: Point,
@@ -35,6 +91,7 @@ pub(crate) struct HitTest {
 #[derive(Debug, Clone, Copy)]
 pub enum HitTestArea {
     Caption,
+    CaptionButton(CaptionButton),
     Resize(Border),
     Client,
 }
@@ -42,6 +99,7 @@ impl HitTestArea {
     pub(crate) fn l_result(&self) -> LRESULT {
         match self {
             Self::Caption => LRESULT(HTCAPTION),
+            Self::CaptionButton(button) => LRESULT(button.hit_test_code()),
             Self::Resize(border) => border.l_result(),
             Self::Client => LRESULT(HTNOWHERE),
         }
@@ -54,7 +112,7 @@ pub(crate) enum ExtentHitTest {
     ClientArea(Point),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(i32)]
 pub enum Border {
     TopLeft = HTTOPLEFT,
@@ -70,6 +128,32 @@ impl Border {
     pub(crate) fn l_result(&self) -> LRESULT {
         LRESULT(*self as i32)
     }
+    pub(crate) fn from_sizing_edge(w_param: WPARAM) -> Option<Self> {
+        let edge = w_param.0 as i32;
+        match edge {
+            _ if edge == WMSZ_TOPLEFT as i32 => Some(Self::TopLeft),
+            _ if edge == WMSZ_TOP as i32 => Some(Self::Top),
+            _ if edge == WMSZ_TOPRIGHT as i32 => Some(Self::TopRight),
+            _ if edge == WMSZ_LEFT as i32 => Some(Self::Left),
+            _ if edge == WMSZ_RIGHT as i32 => Some(Self::Right),
+            _ if edge == WMSZ_BOTTOMLEFT as i32 => Some(Self::BottomLeft),
+            _ if edge == WMSZ_BOTTOM as i32 => Some(Self::Bottom),
+            _ if edge == WMSZ_BOTTOMRIGHT as i32 => Some(Self::BottomRight),
+            _ => None,
+        }
+    }
+    pub fn is_left(&self) -> bool {
+        matches!(self, Self::TopLeft | Self::Left | Self::BottomLeft)
+    }
+    pub fn is_right(&self) -> bool {
+        matches!(self, Self::TopRight | Self::Right | Self::BottomRight)
+    }
+    pub fn is_top(&self) -> bool {
+        matches!(self, Self::TopLeft | Self::Top | Self::TopRight)
+    }
+    pub fn is_bottom(&self) -> bool {
+        matches!(self, Self::BottomLeft | Self::Bottom | Self::BottomRight)
+    }
 }
 
 pub(crate) struct WindowMetrics {
@@ -104,12 +188,35 @@ impl WindowFrameMetrics {
             adjust_caption: caption_frame_rect.top,
         }
     }
+    // The thickness of the non-client area left over after extending the client area.
+    pub(crate) fn non_client_margins(&self, extend_client_area: &Margins) -> Margins {
+        let borders = &self.adjust_resize_borders;
+        Margins {
+            left: -borders.left - extend_client_area.left,
+            top: -self.adjust_caption - extend_client_area.top,
+            right: borders.right - extend_client_area.right,
+            bottom: borders.bottom - extend_client_area.bottom,
+        }
+        .zero_if_sheet()
+    }
+    pub(crate) fn client_to_window_size(&self, size: Size, extend_client_area: &Margins) -> Size {
+        let margins = self.non_client_margins(extend_client_area);
+        Size {
+            width: size.width + margins.left + margins.right,
+            height: size.height + margins.top + margins.bottom,
+        }
+    }
+    // How far a maximized window hangs off each edge of the work area.
+    pub(crate) fn maximized_overhang(&self) -> &RECT {
+        &self.adjust_resize_borders
+    }
 }
 
 pub(crate) unsafe fn non_client_hit_test(
     point: Point,
     metrics: &WindowMetrics,
     options: &WindowFrame,
+    state: &WindowState,
 ) -> HitTest {
     // Get the point coordinates for the hit test.
     let Point { x, y } = point;
@@ -119,7 +226,8 @@ pub(crate) unsafe fn non_client_hit_test(
     // Determine if the hit test is for resizing. Default middle (1,1).
     let mut row = 1;
     let mut col = 1;
-    let top_resize_border = y < window.top - frame.adjust_resize_borders.top;
+    let top_resize_border =
+        state.is_resizable() && y < window.top - frame.adjust_resize_borders.top;
 
     let client_area_top = window.top - frame.adjust_caption - options.extend_client_area.top;
     let client_area_bottom = window.bottom - frame.adjust_resize_borders.bottom;
@@ -161,8 +269,19 @@ pub(crate) unsafe fn non_client_hit_test(
             HitTestArea::Resize(Border::BottomRight),
         ],
     ];
+    let area = match hit_tests[row][col] {
+        // There's nothing to resize, the borders belong to the caption or the client area.
+        HitTestArea::Resize(_) if !state.is_resizable() => {
+            if row == 0 {
+                HitTestArea::Caption
+            } else {
+                HitTestArea::Client
+            }
+        }
+        area => area,
+    };
     HitTest {
-        area: hit_tests[row][col],
+        area,
         client_position: Point {
             x: x - client_area_left,
             y: y - client_area_top,
@@ -229,7 +348,46 @@ pub(crate) unsafe fn extent_hit_test(
     hit_tests[row][col]
 }
 
-pub(crate) unsafe fn transform_hit_test(hit_test: HitTest, options: &WindowFrame) -> HitTestArea {
+// Whether the application marked the spot as caption itself, through a tab strip's drag
+// area or the client area intercept, rather than it only lying in the extended caption
+// where its widgets can be.
+pub(crate) fn is_declared_caption(
+    hit_test: &HitTest,
+    options: &WindowFrame,
+    tab_strip: Option<&TabStrip>,
+) -> bool {
+    match hit_test.area {
+        HitTestArea::Caption => true,
+        HitTestArea::Client => {
+            if let Some(area) =
+                tab_strip.and_then(|tab_strip| tab_strip.hit_test(hit_test.client_position))
+            {
+                return matches!(area, TabStripArea::Drag);
+            }
+            matches!(
+                options
+                    .intercept_client_area_hit_test
+                    .as_ref()
+                    .and_then(|intercept| intercept(
+                        &hit_test.client_position,
+                        &hit_test.client_size
+                    )),
+                Some(HitTestArea::Caption)
+            )
+        }
+        _ => false,
+    }
+}
+
+pub(crate) unsafe fn transform_hit_test(
+    hit_test: HitTest,
+    options: &WindowFrame,
+    state: &WindowState,
+    buttons: &[CaptionButtonRect],
+    tab_strip: Option<&TabStrip>,
+) -> HitTestArea {
+    let hit_test_extended_resize_borders =
+        options.hit_test_extended_resize_borders && state.is_resizable();
     match hit_test.area {
         border @ HitTestArea::Resize(Border::Top) => {
             match options
@@ -243,7 +401,21 @@ pub(crate) unsafe fn transform_hit_test(hit_test: HitTest, options: &WindowFrame
         }
         border @ HitTestArea::Resize(_) => border,
         caption @ HitTestArea::Caption => caption,
+        button @ HitTestArea::CaptionButton(_) => button,
         HitTestArea::Client => {
+            if let Some(button) = buttons
+                .iter()
+                .find(|button| button.rect.contains(hit_test.client_position))
+            {
+                return HitTestArea::CaptionButton(button.button);
+            }
+            if let Some(tab_strip) = tab_strip {
+                match tab_strip.hit_test(hit_test.client_position) {
+                    Some(TabStripArea::Tab(_)) => return HitTestArea::Client,
+                    Some(TabStripArea::Drag) => return HitTestArea::Caption,
+                    None => {}
+                }
+            }
             match options
                 .intercept_client_area_hit_test
                 .as_ref()
@@ -258,25 +430,23 @@ pub(crate) unsafe fn transform_hit_test(hit_test: HitTest, options: &WindowFrame
                         ExtentHitTest::Extent(Border::TopLeft)
                         | ExtentHitTest::Extent(Border::TopRight)
                             if options.hit_test_extended_caption
-                                && !options.hit_test_extended_resize_borders =>
+                                && !hit_test_extended_resize_borders =>
                         {
                             HitTestArea::Caption
                         }
                         ExtentHitTest::Extent(Border::TopLeft)
                             if options.hit_test_extended_caption
-                                && options.hit_test_extended_resize_borders =>
+                                && hit_test_extended_resize_borders =>
                         {
                             HitTestArea::Resize(Border::Left)
                         }
                         ExtentHitTest::Extent(Border::TopRight)
                             if options.hit_test_extended_caption
-                                && options.hit_test_extended_resize_borders =>
+                                && hit_test_extended_resize_borders =>
                         {
                             HitTestArea::Resize(Border::Right)
                         }
-                        ExtentHitTest::Extent(border)
-                            if options.hit_test_extended_resize_borders =>
-                        {
+                        ExtentHitTest::Extent(border) if hit_test_extended_resize_borders => {
                             HitTestArea::Resize(border)
                         }
                         _ => HitTestArea::Client,
@@ -294,9 +464,116 @@ fn get_l_param_point(lp: LPARAM) -> (i32, i32) {
     )
 }
 
-const fn lo_word(l: u32) -> u16 {
+const fn make_l_param(lo: u16, hi: u16) -> u32 {
+    (lo as u32) | ((hi as u32) << 16)
+}
+
+pub(crate) const fn lo_word(l: u32) -> u16 {
     (l & 0xffff) as u16
 }
-const fn hi_word(l: u32) -> u16 {
+pub(crate) const fn hi_word(l: u32) -> u16 {
     ((l >> 16) & 0xffff) as u16
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> WindowFrameMetrics {
+        WindowFrameMetrics {
+            adjust_resize_borders: RECT {
+                left: -8,
+                top: -8,
+                right: 8,
+                bottom: 8,
+            },
+            adjust_caption: -31,
+        }
+    }
+
+    fn size(width: i32, height: i32) -> Size {
+        Size { width, height }
+    }
+
+    #[test]
+    fn client_to_window_size_adds_the_native_frame() {
+        assert_eq!(
+            frame().client_to_window_size(size(800, 600), &Margins::default()),
+            size(816, 639)
+        );
+    }
+
+    #[test]
+    fn client_to_window_size_leaves_out_the_extended_client_area() {
+        let extend_client_area = Margins {
+            left: 0,
+            top: 31,
+            right: 0,
+            bottom: 0,
+        };
+        assert_eq!(
+            frame().client_to_window_size(size(800, 600), &extend_client_area),
+            size(816, 608)
+        );
+        let extend_client_area = Margins {
+            left: 8,
+            top: 31,
+            right: 8,
+            bottom: 8,
+        };
+        assert_eq!(
+            frame().client_to_window_size(size(800, 600), &extend_client_area),
+            size(800, 600)
+        );
+    }
+
+    #[test]
+    fn client_to_window_size_never_shrinks_below_the_client_size() {
+        let extend_client_area = Margins {
+            left: 20,
+            top: 40,
+            right: 20,
+            bottom: 20,
+        };
+        assert_eq!(
+            frame().client_to_window_size(size(800, 600), &extend_client_area),
+            size(800, 600)
+        );
+    }
+
+    fn client_hit_test(x: i32, y: i32) -> HitTest {
+        HitTest {
+            area: HitTestArea::Client,
+            client_position: Point { x, y },
+            client_size: size(800, 600),
+        }
+    }
+
+    #[test]
+    fn only_the_intercept_declares_the_extended_caption() {
+        let mut options = WindowFrame::extended_caption(0);
+        assert!(!is_declared_caption(
+            &client_hit_test(100, 10),
+            &options,
+            None
+        ));
+
+        options.intercept_client_area_hit_test = Some(Box::new(|position: &Point, _: &Size| {
+            if position.x < 200 {
+                Some(HitTestArea::Caption)
+            } else {
+                Some(HitTestArea::Client)
+            }
+        }));
+        assert!(is_declared_caption(
+            &client_hit_test(100, 10),
+            &options,
+            None
+        ));
+        assert!(!is_declared_caption(
+            &client_hit_test(300, 10),
+            &options,
+            None
+        ));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::{
+    bindings::windows::win32::windows_and_messaging::HWND,
+    caption_buttons::CaptionButton,
+    dark_mode::windows_10_build,
+    hit_test::{Point, Rect, WindowFrameMetrics},
+    options::{system_caption_height, Margins},
+    util::{caption_height_for_dpi, window_dpi},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionSide {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionStyle {
+    Windows10,
+    Windows11,
+}
+impl CaptionStyle {
+    pub fn system() -> Self {
+        match windows_10_build() {
+            Some(build) if build >= 22000 => Self::Windows11,
+            _ => Self::Windows10,
+        }
+    }
+    // Button width at 96 DPI.
+    fn button_width(&self) -> i32 {
+        match self {
+            Self::Windows10 => 45,
+            Self::Windows11 => 46,
+        }
+    }
+    // Windows 10 leaves the one pixel window border above the buttons, at 96 DPI.
+    fn top_inset(&self) -> i32 {
+        match self {
+            Self::Windows10 => 1,
+            Self::Windows11 => 0,
+        }
+    }
+}
+
+/// The parts of the frame metrics that decide where caption buttons go.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptionMetrics {
+    pub caption_height: i32,
+    /// The window's DPI scale factor, `1.0` at 96 DPI.
+    pub scale: f32,
+    /// How far a maximized window's client area hangs off the top of the screen.
+    pub maximized_top: i32,
+    pub maximized_left: i32,
+    pub maximized_right: i32,
+}
+impl CaptionMetrics {
+    pub fn system(extend_client_area: &Margins) -> Self {
+        let frame = unsafe { WindowFrameMetrics::new() };
+        let overhang = frame.maximized_overhang();
+        let non_client = frame.non_client_margins(extend_client_area);
+        Self {
+            caption_height: system_caption_height(),
+            scale: 1.0,
+            maximized_top: (-overhang.top - non_client.top).max(0),
+            maximized_left: (-overhang.left - non_client.left).max(0),
+            maximized_right: (overhang.right - non_client.right).max(0),
+        }
+    }
+    /// The system metrics at the DPI of the monitor `h_wnd` is on.
+    pub(crate) unsafe fn window(h_wnd: HWND, extend_client_area: &Margins) -> Self {
+        let system = Self::system(extend_client_area);
+        match window_dpi(h_wnd) {
+            Some(dpi) => Self {
+                caption_height: caption_height_for_dpi(dpi).unwrap_or(system.caption_height),
+                scale: dpi as f32 / 96.0,
+                ..system
+            },
+            None => system,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptionButtonRect {
+    pub button: CaptionButton,
+    pub rect: Rect,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptionButtonLayout {
+    /// The buttons in left to right order.
+    pub buttons: Vec<CaptionButton>,
+    pub side: CaptionSide,
+    /// Mirrors the layout, for right-to-left window layouts.
+    pub right_to_left: bool,
+    pub style: CaptionStyle,
+    /// Scales the buttons on top of the window's DPI scale.
+    pub scale: f32,
+    /// At 96 DPI. Defaults to the system caption height.
+    pub button_height: Option<i32>,
+}
+impl Default for CaptionButtonLayout {
+    fn default() -> Self {
+        Self {
+            buttons: vec![
+                CaptionButton::Minimize,
+                CaptionButton::Maximize,
+                CaptionButton::Close,
+            ],
+            side: CaptionSide::Right,
+            right_to_left: false,
+            style: CaptionStyle::system(),
+            scale: 1.0,
+            button_height: None,
+        }
+    }
+}
+impl CaptionButtonLayout {
+    /// Computes the button rectangles in client coordinates.
+    pub fn layout(
+        &self,
+        client_width: i32,
+        metrics: &CaptionMetrics,
+        maximized: bool,
+    ) -> Vec<CaptionButtonRect> {
+        let scale = metrics.scale * self.scale;
+        let scaled = |length: i32| (length as f32 * scale).round() as i32;
+        let width = scaled(self.style.button_width());
+        let (top, left_inset, right_inset) = if maximized {
+            (
+                metrics.maximized_top,
+                metrics.maximized_left,
+                metrics.maximized_right,
+            )
+        } else {
+            (scaled(self.style.top_inset()), 0, 0)
+        };
+        let height = self
+            .button_height
+            .map(scaled)
+            .unwrap_or(metrics.caption_height)
+            - top;
+
+        let total_width = width * self.buttons.len() as i32;
+        let start = match self.side {
+            CaptionSide::Left => left_inset,
+            CaptionSide::Right => client_width - right_inset - total_width,
+        };
+
+        self.buttons
+            .iter()
+            .enumerate()
+            .map(|(index, button)| {
+                let left = start + width * index as i32;
+                let rect = Rect {
+                    left,
+                    top,
+                    right: left + width,
+                    bottom: top + height,
+                };
+                CaptionButtonRect {
+                    button: *button,
+                    rect: if self.right_to_left {
+                        mirror(rect, client_width)
+                    } else {
+                        rect
+                    },
+                }
+            })
+            .collect()
+    }
+    pub fn hit_test(
+        &self,
+        point: Point,
+        client_width: i32,
+        metrics: &CaptionMetrics,
+        maximized: bool,
+    ) -> Option<CaptionButton> {
+        self.layout(client_width, metrics, maximized)
+            .into_iter()
+            .find(|button| button.rect.contains(point))
+            .map(|button| button.button)
+    }
+}
+
+fn mirror(rect: Rect, width: i32) -> Rect {
+    Rect {
+        left: width - rect.right,
+        right: width - rect.left,
+        ..rect
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: CaptionMetrics = CaptionMetrics {
+        caption_height: 31,
+        scale: 1.0,
+        maximized_top: 8,
+        maximized_left: 8,
+        maximized_right: 8,
+    };
+
+    fn layout(side: CaptionSide, style: CaptionStyle) -> CaptionButtonLayout {
+        CaptionButtonLayout {
+            buttons: vec![
+                CaptionButton::Minimize,
+                CaptionButton::Maximize,
+                CaptionButton::Close,
+            ],
+            side,
+            right_to_left: false,
+            style,
+            scale: 1.0,
+            button_height: None,
+        }
+    }
+
+    fn rects(buttons: &[CaptionButtonRect]) -> Vec<(i32, i32, i32, i32)> {
+        buttons
+            .iter()
+            .map(|button| {
+                let rect = button.rect;
+                (rect.left, rect.top, rect.right, rect.bottom)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn right_side_buttons_end_at_the_client_edge() {
+        let buttons =
+            layout(CaptionSide::Right, CaptionStyle::Windows10).layout(1000, &METRICS, false);
+        assert_eq!(
+            rects(&buttons),
+            vec![(865, 1, 910, 31), (910, 1, 955, 31), (955, 1, 1000, 31)]
+        );
+        assert_eq!(buttons[2].button, CaptionButton::Close);
+    }
+
+    #[test]
+    fn left_side_buttons_scale_with_dpi() {
+        let metrics = CaptionMetrics {
+            caption_height: 45,
+            scale: 1.5,
+            ..METRICS
+        };
+        let mut layout = layout(CaptionSide::Left, CaptionStyle::Windows11);
+        assert_eq!(
+            rects(&layout.layout(1000, &metrics, false)),
+            vec![(0, 0, 69, 45), (69, 0, 138, 45), (138, 0, 207, 45)]
+        );
+
+        layout.button_height = Some(32);
+        assert_eq!(
+            rects(&layout.layout(1000, &metrics, false)),
+            vec![(0, 0, 69, 48), (69, 0, 138, 48), (138, 0, 207, 48)]
+        );
+
+        layout.style = CaptionStyle::Windows10;
+        layout.scale = 2.0 / 1.5;
+        assert_eq!(
+            rects(&layout.layout(1000, &metrics, false)),
+            vec![(0, 2, 90, 64), (90, 2, 180, 64), (180, 2, 270, 64)]
+        );
+    }
+
+    #[test]
+    fn right_to_left_mirrors_the_buttons() {
+        let mut layout = layout(CaptionSide::Right, CaptionStyle::Windows10);
+        layout.right_to_left = true;
+        let buttons = layout.layout(1000, &METRICS, false);
+        assert_eq!(
+            rects(&buttons),
+            vec![(90, 1, 135, 31), (45, 1, 90, 31), (0, 1, 45, 31)]
+        );
+        assert_eq!(buttons[2].button, CaptionButton::Close);
+    }
+
+    #[test]
+    fn maximized_buttons_stay_on_screen() {
+        let buttons =
+            layout(CaptionSide::Right, CaptionStyle::Windows10).layout(1000, &METRICS, true);
+        assert_eq!(
+            rects(&buttons),
+            vec![(857, 8, 902, 31), (902, 8, 947, 31), (947, 8, 992, 31)]
+        );
+    }
+
+    #[test]
+    fn hit_test_finds_the_button_under_the_point() {
+        let layout = layout(CaptionSide::Right, CaptionStyle::Windows10);
+        let hit = |x, y| layout.hit_test(Point { x, y }, 1000, &METRICS, false);
+        assert_eq!(hit(999, 10), Some(CaptionButton::Close));
+        assert_eq!(hit(910, 30), Some(CaptionButton::Maximize));
+        assert_eq!(hit(864, 10), None);
+        assert_eq!(hit(950, 31), None);
+    }
+}
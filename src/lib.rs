@@ -1,110 +1,282 @@
+mod accessibility;
 mod bindings {
     ::windows::include_bindings!();
 }
+mod caption_buttons;
+mod command;
 mod dark_mode;
+mod drag;
+mod drag_out;
+mod fallback;
+mod fullscreen;
+mod glyphs;
 mod hit_test;
+mod layout;
+mod min_max;
 mod options;
+mod pointer;
+mod reveal;
+mod sizing;
+mod snapping;
+mod state;
 mod subclass;
+mod tab_strip;
+mod title_bar;
+mod title_layout;
+mod uia;
 mod util;
+mod window_source;
 
 use {
     bindings::windows::win32::{
-        display_devices::RECT,
         dwm::DwmExtendFrameIntoClientArea,
         shell::{RemoveWindowSubclass, SetWindowSubclass},
-        system_services::SWP_FRAMECHANGED,
-        windows_and_messaging::{GetWindowRect, SetWindowPos, HWND},
+        windows_and_messaging::{KillTimer, HWND, WINDOWPLACEMENT},
     },
-    dark_mode::dark_dwm_decorations,
-    raw_window_handle::HasRawWindowHandle,
+    dark_mode::{dark_dwm_decorations, is_dark_dwm_decorations},
     std::{
         cell::Cell,
         ops::{Deref, DerefMut},
     },
-    subclass::subclass_procedure,
-    util::{window_frame_borders, windows_window_handle},
+    subclass::{subclass_procedure, SubclassData, CAPTION_REVEAL_TIMER},
+    util::{frame_changed, window_frame_borders},
 };
+
 pub use {
+    accessibility::{AccessibilityTree, AccessibleNode, AccessibleRole},
+    caption_buttons::{
+        ButtonState, CaptionButton, CaptionButtonEvent, CaptionButtons, CaptionGlyph,
+    },
+    command::WindowCommand,
     dark_mode::Theme,
-    hit_test::{HitTestArea, Point, Size},
+    drag_out::{DragOut, DragOutDirection, DragOutEvent, DragOutTracker},
+    glyphs::{draw_caption_button, draw_glyph, GlyphColors, PixelFormat},
+    hit_test::{Border, HitTestArea, Point, Rect, Size},
+    layout::{CaptionButtonLayout, CaptionButtonRect, CaptionMetrics, CaptionSide, CaptionStyle},
     options::*,
+    pointer::{HitTargetSize, PointerHitTargets, PointerType},
+    reveal::{CaptionReveal, RevealEvent},
+    snapping::{SnapGroup, Snapping},
+    state::WindowState,
+    tab_strip::{TabStrip, TabStripArea, TabStripLayout},
+    title_bar::{DrawTitleCallback, TitleBar, TitleBarColors, TitleBarIcon, TitleBarRenderer},
+    title_layout::{layout_title, MeasureTitleCallback, TitleAlignment, TitleGlyph, TitleLayout},
+    window_source::*,
 };
 
 pub struct WindowCustomization {
     handle: HWND,
     subclass_id: usize,
-    options: Box<WindowFrame>,
+    data: Box<SubclassData>,
     is_set: Cell<bool>,
+    original_dark_mode: Cell<Option<bool>>,
+    saved_placement: Cell<Option<WINDOWPLACEMENT>>,
 }
 impl WindowCustomization {
-    pub fn new<W: HasRawWindowHandle>(window: &W, options: WindowFrame) -> windows::Result<Self> {
+    pub fn new<M, W: WindowSource<M>>(window: &W, options: WindowFrame) -> windows::Result<Self> {
         Self::with_id(window, options, 1)
     }
-    pub fn with_id<W: HasRawWindowHandle>(
+    pub fn with_id<M, W: WindowSource<M>>(
         window: &W,
         options: WindowFrame,
         subclass_id: usize,
     ) -> windows::Result<Self> {
-        let handle = windows_window_handle(window);
+        unsafe { Self::from_hwnd_with_id(window.hwnd(), options, subclass_id) }
+    }
+    /// # Safety
+    ///
+    /// `hwnd` must be a valid top-level window owned by the calling thread, and it must
+    /// outlive the returned customization.
+    pub unsafe fn from_hwnd(hwnd: isize, options: WindowFrame) -> windows::Result<Self> {
+        Self::from_hwnd_with_id(hwnd, options, 1)
+    }
+    /// # Safety
+    ///
+    /// See [`WindowCustomization::from_hwnd`]. `subclass_id` must not already be in use by
+    /// another subclass of `subclass_procedure` on the same window.
+    pub unsafe fn from_hwnd_with_id(
+        hwnd: isize,
+        options: WindowFrame,
+        subclass_id: usize,
+    ) -> windows::Result<Self> {
         let customization = Self {
-            handle,
+            handle: HWND(hwnd),
             subclass_id,
-            options: Box::new(options),
+            data: Box::new(SubclassData::new(options)),
             is_set: Cell::new(false),
+            original_dark_mode: Cell::new(None),
+            saved_placement: Cell::new(None),
         };
-        unsafe {
-            customization.set()?;
-        }
+        customization.set()?;
         Ok(customization)
     }
     pub unsafe fn set(&self) -> windows::Result<()> {
-        let options_ptr = &*self.options as *const WindowFrame;
+        if self.original_dark_mode.get().is_none() {
+            self.original_dark_mode
+                .set(Some(is_dark_dwm_decorations(self.handle).unwrap_or(false)));
+        }
+        self.data
+            .state
+            .set(WindowState::query(self.handle, self.data.fullscreen.get()));
+        let mut buttons = self.data.caption_buttons.get();
+        buttons.set_maximized(self.data.state.get().maximized);
+        self.data.caption_buttons.set(buttons);
+        let data_ptr = &*self.data as *const SubclassData;
         SetWindowSubclass(
             self.handle,
             Some(subclass_procedure),
             self.subclass_id,
-            options_ptr as usize,
+            data_ptr as usize,
         )
         .ok()?;
         self.is_set.set(true);
         self.update();
         Ok(())
     }
+    pub fn window_state(&self) -> WindowState {
+        self.data.state.get()
+    }
+    pub fn caption_buttons(&self) -> CaptionButtons {
+        self.data.caption_buttons.get()
+    }
+    /// Where the caption buttons are, in client coordinates, according to
+    /// `WindowFrame::caption_button_layout`.
+    pub fn caption_button_rects(&self) -> Vec<CaptionButtonRect> {
+        unsafe { self.data.caption_button_rects(self.handle) }
+    }
+    /// Everything a `TitleBarRenderer` needs to paint this window's caption.
+    pub fn title_bar<'a>(&self, title: &'a str) -> TitleBar<'a> {
+        unsafe { self.data.title_bar(self.handle, title) }
+    }
+    /// Where the tabs are, according to `WindowFrame::tab_strip`.
+    pub fn tab_strip(&self) -> Option<TabStrip> {
+        unsafe { self.data.tab_strip(self.handle) }
+    }
     pub fn edit(&mut self) -> WindowFrameMut {
         WindowFrameMut {
             customization: self,
         }
     }
     unsafe fn update(&self) {
-        if let Some(theme) = &self.options.theme {
+        if let Some(theme) = &self.data.options.theme {
             dark_dwm_decorations(self.handle, matches!(theme, Theme::Dark));
         }
 
-        let mut rect = RECT::default();
-        GetWindowRect(self.handle, &mut rect);
+        let p_mar_inset = self.data.extend_frame().to_win32();
 
-        // Inform application of the frame change.
-        let width = rect.right - rect.left;
-        let height = rect.bottom - rect.top;
+        frame_changed(self.handle);
+        DwmExtendFrameIntoClientArea(self.handle, &p_mar_inset);
+        self.data.update_layout(self.handle);
+    }
+    /// Starts moving the window as if its caption had been dragged.
+    ///
+    /// Call this from the window's thread while the left mouse button is held down.
+    pub fn begin_drag_move(&self) -> windows::Result<()> {
+        unsafe { drag::begin_drag_move(self.handle) }
+    }
+    /// Moves the window so the cursor is `offset` from the top left corner of an item at
+    /// `item_origin`, in client coordinates, then starts moving it with the mouse, e.g. for
+    /// a tab that was dragged out of another window.
+    ///
+    /// Call this from the window's thread while the left mouse button is held down.
+    pub fn continue_drag_move(&self, offset: Point, item_origin: Point) -> windows::Result<()> {
+        unsafe { drag::continue_drag_move(self.handle, offset, item_origin) }
+    }
+    /// Starts resizing the window as if `border` had been dragged.
+    ///
+    /// Call this from the window's thread while the left mouse button is held down.
+    pub fn begin_resize(&self, border: Border) -> windows::Result<()> {
+        unsafe { drag::begin_resize(self.handle, border) }
+    }
+    pub fn execute(&self, command: WindowCommand) {
+        unsafe { command::execute(self.handle, command) }
+    }
+    pub fn toggle_maximize(&self) {
+        unsafe { command::toggle_maximize(self.handle) }
+    }
+    /// Shows the window's system menu at `position`, in screen coordinates.
+    pub fn show_system_menu_at(&self, position: Point) {
+        unsafe { command::show_system_menu_at(self.handle, position, &self.data.state.get()) }
+    }
+    pub fn is_fullscreen(&self) -> bool {
+        self.data.fullscreen.get()
+    }
+    /// The kind of pointer that was last over the window.
+    pub fn pointer_type(&self) -> PointerType {
+        self.data.pointer_type.get()
+    }
+    /// Covers the window's monitor, suspending the extended frame, resize borders and
+    /// caption hit testing until [`WindowCustomization::exit_fullscreen`].
+    pub fn enter_fullscreen(&self) -> windows::Result<()> {
+        if self.data.fullscreen.get() {
+            return Ok(());
+        }
+        unsafe {
+            self.saved_placement
+                .set(Some(fullscreen::save_placement(self.handle)?));
+            self.data.fullscreen.set(true);
+            self.data.update_state(|state| state.fullscreen = true);
 
-        let p_mar_inset = self.options.extend_frame.to_win32();
+            let p_mar_inset = Margins::default().to_win32();
+            DwmExtendFrameIntoClientArea(self.handle, &p_mar_inset);
+            fullscreen::cover_monitor(self.handle);
+        }
+        Ok(())
+    }
+    pub fn exit_fullscreen(&self) {
+        if !self.data.fullscreen.get() {
+            return;
+        }
+        unsafe {
+            self.data.fullscreen.set(false);
+            self.reset_caption_reveal();
+            self.data.update_state(|state| state.fullscreen = false);
 
-        SetWindowPos(
-            self.handle,
-            HWND(0),
-            rect.left,
-            rect.top,
-            width,
-            height,
-            SWP_FRAMECHANGED as _,
-        );
-        DwmExtendFrameIntoClientArea(self.handle, &p_mar_inset);
+            if let Some(placement) = self.saved_placement.take() {
+                fullscreen::restore_placement(self.handle, &placement);
+            }
+            self.update();
+        }
+    }
+    unsafe fn reset_caption_reveal(&self) {
+        KillTimer(self.handle, CAPTION_REVEAL_TIMER);
+        let mut tracker = self.data.caption_reveal.get();
+        let event = tracker.reset();
+        self.data.caption_reveal.set(tracker);
+        if let Some(CaptionReveal {
+            on_event: Some(on_event),
+            ..
+        }) = &self.data.options.caption_reveal
+        {
+            if let Some(event) = event {
+                on_event(event);
+            }
+        }
     }
     pub unsafe fn remove(&self) -> windows::Result<()> {
         RemoveWindowSubclass(self.handle, Some(subclass_procedure), self.subclass_id).ok()?;
         self.is_set.set(false);
+        self.data.fullscreen.set(false);
+        self.reset_caption_reveal();
+        if let Some(placement) = self.saved_placement.take() {
+            fullscreen::restore_placement(self.handle, &placement);
+        }
+        self.restore();
         Ok(())
     }
+    unsafe fn restore(&self) {
+        if self.data.options.theme.is_some() {
+            if let Some(dark_mode) = self.original_dark_mode.get() {
+                dark_dwm_decorations(self.handle, dark_mode);
+            }
+        }
+
+        let p_mar_inset = Margins::default().to_win32();
+        DwmExtendFrameIntoClientArea(self.handle, &p_mar_inset);
+
+        // Let the default window procedure recalculate the non-client area.
+        frame_changed(self.handle);
+    }
 }
 impl Drop for WindowCustomization {
     fn drop(&mut self) {
@@ -116,19 +288,25 @@ impl Drop for WindowCustomization {
     }
 }
 
-pub struct CustomizedWindow<W: HasRawWindowHandle> {
+pub struct CustomizedWindow<W> {
     window: W,
     customization: WindowCustomization,
 }
-impl<W: HasRawWindowHandle> CustomizedWindow<W> {
-    pub fn wrap(window: W, options: WindowFrame) -> windows::Result<Self> {
+impl<W> CustomizedWindow<W> {
+    pub fn wrap<M>(window: W, options: WindowFrame) -> windows::Result<Self>
+    where
+        W: WindowSource<M>,
+    {
         Self::wrap_with_id(window, options, 1)
     }
-    pub fn wrap_with_id(
+    pub fn wrap_with_id<M>(
         window: W,
         options: WindowFrame,
         subclass_id: usize,
-    ) -> windows::Result<Self> {
+    ) -> windows::Result<Self>
+    where
+        W: WindowSource<M>,
+    {
         let customization = WindowCustomization::with_id(&window, options, subclass_id)?;
         let subclassed = Self {
             window,
@@ -142,15 +320,60 @@ impl<W: HasRawWindowHandle> CustomizedWindow<W> {
     pub fn edit_custom_frame(&mut self) -> WindowFrameMut {
         self.customization.edit()
     }
+    pub fn window_state(&self) -> WindowState {
+        self.customization.window_state()
+    }
+    pub fn caption_buttons(&self) -> CaptionButtons {
+        self.customization.caption_buttons()
+    }
+    pub fn caption_button_rects(&self) -> Vec<CaptionButtonRect> {
+        self.customization.caption_button_rects()
+    }
+    pub fn title_bar<'a>(&self, title: &'a str) -> TitleBar<'a> {
+        self.customization.title_bar(title)
+    }
+    pub fn tab_strip(&self) -> Option<TabStrip> {
+        self.customization.tab_strip()
+    }
+    pub fn is_fullscreen(&self) -> bool {
+        self.customization.is_fullscreen()
+    }
+    pub fn pointer_type(&self) -> PointerType {
+        self.customization.pointer_type()
+    }
+    pub fn enter_fullscreen(&self) -> windows::Result<()> {
+        self.customization.enter_fullscreen()
+    }
+    pub fn exit_fullscreen(&self) {
+        self.customization.exit_fullscreen()
+    }
+    pub fn begin_drag_move(&self) -> windows::Result<()> {
+        self.customization.begin_drag_move()
+    }
+    pub fn continue_drag_move(&self, offset: Point, item_origin: Point) -> windows::Result<()> {
+        self.customization.continue_drag_move(offset, item_origin)
+    }
+    pub fn begin_resize(&self, border: Border) -> windows::Result<()> {
+        self.customization.begin_resize(border)
+    }
+    pub fn execute(&self, command: WindowCommand) {
+        self.customization.execute(command)
+    }
+    pub fn toggle_maximize(&self) {
+        self.customization.toggle_maximize()
+    }
+    pub fn show_system_menu_at(&self, position: Point) {
+        self.customization.show_system_menu_at(position)
+    }
 }
-impl<W: HasRawWindowHandle> Deref for CustomizedWindow<W> {
+impl<W> Deref for CustomizedWindow<W> {
     type Target = W;
 
     fn deref(&self) -> &Self::Target {
         &self.window
     }
 }
-impl<W: HasRawWindowHandle> DerefMut for CustomizedWindow<W> {
+impl<W> DerefMut for CustomizedWindow<W> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.window
     }
@@ -163,12 +386,12 @@ impl<'a> Deref for WindowFrameMut<'a> {
     type Target = WindowFrame;
 
     fn deref(&self) -> &Self::Target {
-        &*self.customization.options
+        &self.customization.data.options
     }
 }
 impl<'a> DerefMut for WindowFrameMut<'a> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut *self.customization.options
+        &mut self.customization.data.options
     }
 }
 impl<'a> Drop for WindowFrameMut<'a> {
@@ -179,10 +402,10 @@ impl<'a> Drop for WindowFrameMut<'a> {
     }
 }
 
-pub trait CustomWindowFrame: HasRawWindowHandle + Sized {
+pub trait CustomWindowFrame<M>: WindowSource<M> + Sized {
     fn customize_frame(self, options: WindowFrame) -> windows::Result<CustomizedWindow<Self>>;
 }
-impl<W: HasRawWindowHandle> CustomWindowFrame for W {
+impl<M, W: WindowSource<M>> CustomWindowFrame<M> for W {
     fn customize_frame(self, options: WindowFrame) -> windows::Result<CustomizedWindow<Self>> {
         CustomizedWindow::wrap(self, options)
     }
@@ -0,0 +1,39 @@
+use crate::{
+    bindings::windows::win32::windows_and_messaging::{HWND, MINMAXINFO},
+    hit_test::WindowFrameMetrics,
+    options::WindowFrame,
+    util::monitor_info,
+};
+
+pub(crate) unsafe fn adjust_min_max_info(
+    h_wnd: HWND,
+    info: &mut MINMAXINFO,
+    options: &WindowFrame,
+) {
+    let frame = WindowFrameMetrics::new();
+
+    if let Some(min_client_size) = options.min_client_size {
+        let min_size = frame.client_to_window_size(min_client_size, &options.extend_client_area);
+        info.pt_min_track_size.x = info.pt_min_track_size.x.max(min_size.width);
+        info.pt_min_track_size.y = info.pt_min_track_size.y.max(min_size.height);
+    }
+    if let Some(max_client_size) = options.max_client_size {
+        let max_size = frame.client_to_window_size(max_client_size, &options.extend_client_area);
+        info.pt_max_track_size.x = info.pt_max_track_size.x.min(max_size.width);
+        info.pt_max_track_size.y = info.pt_max_track_size.y.min(max_size.height);
+    }
+
+    // The default maximized rectangle is based on the primary monitor, fit it to the work
+    // area of the monitor the window is actually on. This replaces the application's own
+    // maximized rectangle.
+    if let Some(monitor_info) = monitor_info(h_wnd) {
+        let work = monitor_info.rc_work;
+        let monitor = monitor_info.rc_monitor;
+        let overhang = frame.maximized_overhang();
+
+        info.pt_max_position.x = work.left - monitor.left + overhang.left;
+        info.pt_max_position.y = work.top - monitor.top + overhang.top;
+        info.pt_max_size.x = (work.right - work.left) - overhang.left + overhang.right;
+        info.pt_max_size.y = (work.bottom - work.top) - overhang.top + overhang.bottom;
+    }
+}
@@ -1,8 +1,14 @@
 use crate::{
     bindings::windows::win32::controls::MARGINS,
+    caption_buttons::CaptionButtonEvent,
+    dark_mode::Theme,
     hit_test::{HitTestArea, Point, Size},
+    layout::CaptionButtonLayout,
+    pointer::PointerHitTargets,
+    reveal::CaptionReveal,
+    snapping::Snapping,
+    tab_strip::{TabStrip, TabStripLayout},
     window_frame_borders,
-    dark_mode::Theme,
 };
 
 #[derive(Default)]
@@ -10,24 +16,92 @@ pub struct WindowFrame {
     pub theme: Option<Theme>,
     pub extend_frame: Margins,
     pub extend_client_area: Margins,
+    pub min_client_size: Option<Size>,
+    pub max_client_size: Option<Size>,
+    pub aspect_ratio: Option<f64>,
+    pub resize_increment: Option<Size>,
+    pub resize_base_size: Size,
+    pub on_grid_resize: Option<GridResizeCallback>,
+    pub snapping: Option<Snapping>,
     pub hit_test_caption_buttons: bool,
     pub hit_test_extended_caption: bool,
     pub hit_test_extended_resize_borders: bool,
     pub intercept_client_area_hit_test: Option<HitTestIntercept>,
     pub intercept_top_resize_border_hit_test: Option<HitTestIntercept>,
+    pub caption_button_layout: Option<CaptionButtonLayout>,
+    pub on_caption_button_event: Option<CaptionButtonCallback>,
+    pub caption_reveal: Option<CaptionReveal>,
+    pub caption_double_click: CaptionAction,
+    /// Middle clicks are left to the system when this is `None`.
+    pub caption_middle_click: Option<CaptionAction>,
+    pub caption_drag: CaptionDrag,
+    /// Tabs are client area, the space around them moves the window.
+    pub tab_strip: Option<TabStripLayout>,
+    /// Called when resizing the window moved the tabs.
+    pub on_tab_strip_layout: Option<TabStripCallback>,
+    /// Larger resize borders and corner grips for touch and pen.
+    pub pointer_hit_targets: PointerHitTargets,
 }
 pub type HitTestIntercept = Box<dyn Fn(&Point, &Size) -> Option<HitTestArea>>;
+pub type GridResizeCallback = Box<dyn Fn(Size)>;
+pub type CaptionButtonCallback = Box<dyn Fn(CaptionButtonEvent)>;
+/// Called with the cursor position in screen coordinates.
+pub type CaptionClickCallback = Box<dyn Fn(Point)>;
+pub type TabStripCallback = Box<dyn Fn(&TabStrip)>;
+
+/// What clicking on an area the hit test reports as `HitTestArea::Caption` does.
+#[derive(Default)]
+pub enum CaptionAction {
+    Ignore,
+    Minimize,
+    #[default]
+    ToggleMaximize,
+    Callback(CaptionClickCallback),
+}
+
+/// When pressing on an area the hit test reports as `HitTestArea::Caption` starts moving
+/// the window.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionDrag {
+    /// Right away, like the native caption.
+    #[default]
+    Immediate,
+    /// Once the mouse moves past the system drag threshold. Until then the area is client
+    /// area, so widgets placed in it get clicks as usual.
+    ///
+    /// Double clicks, middle clicks and right clicks only run their caption actions where the
+    /// application marks the caption itself, through `intercept_client_area_hit_test` or a
+    /// tab strip's drag area; elsewhere they reach the window as usual. Double clicks also
+    /// need the `CS_DBLCLKS` window class style.
+    AfterThreshold,
+}
 impl WindowFrame {
     pub fn extended_caption(extra_height: i32) -> Self {
         Self {
             theme: None,
             extend_frame: Margins::caption(extra_height),
             extend_client_area: Margins::default(),
+            min_client_size: None,
+            max_client_size: None,
+            aspect_ratio: None,
+            resize_increment: None,
+            resize_base_size: Size::default(),
+            on_grid_resize: None,
+            snapping: None,
             hit_test_caption_buttons: true,
             hit_test_extended_caption: true,
             hit_test_extended_resize_borders: false,
             intercept_client_area_hit_test: None,
             intercept_top_resize_border_hit_test: None,
+            caption_button_layout: None,
+            on_caption_button_event: None,
+            caption_reveal: None,
+            caption_double_click: CaptionAction::ToggleMaximize,
+            caption_middle_click: None,
+            caption_drag: CaptionDrag::Immediate,
+            tab_strip: None,
+            on_tab_strip_layout: None,
+            pointer_hit_targets: PointerHitTargets::default(),
         }
     }
     pub fn sheet() -> Self {
@@ -35,11 +109,27 @@ impl WindowFrame {
             theme: None,
             extend_frame: Margins::sheet(),
             extend_client_area: Margins::default(),
+            min_client_size: None,
+            max_client_size: None,
+            aspect_ratio: None,
+            resize_increment: None,
+            resize_base_size: Size::default(),
+            on_grid_resize: None,
+            snapping: None,
             hit_test_caption_buttons: true,
             hit_test_extended_caption: true,
             hit_test_extended_resize_borders: false,
             intercept_client_area_hit_test: None,
             intercept_top_resize_border_hit_test: None,
+            caption_button_layout: None,
+            on_caption_button_event: None,
+            caption_reveal: None,
+            caption_double_click: CaptionAction::ToggleMaximize,
+            caption_middle_click: None,
+            caption_drag: CaptionDrag::Immediate,
+            tab_strip: None,
+            on_tab_strip_layout: None,
+            pointer_hit_targets: PointerHitTargets::default(),
         }
     }
     pub fn custom_caption() -> Self {
@@ -47,11 +137,27 @@ impl WindowFrame {
             theme: None,
             extend_frame: Margins::default_caption(),
             extend_client_area: Margins::default_caption(),
+            min_client_size: None,
+            max_client_size: None,
+            aspect_ratio: None,
+            resize_increment: None,
+            resize_base_size: Size::default(),
+            on_grid_resize: None,
+            snapping: None,
             hit_test_caption_buttons: true,
             hit_test_extended_caption: true,
             hit_test_extended_resize_borders: false,
             intercept_client_area_hit_test: None,
             intercept_top_resize_border_hit_test: None,
+            caption_button_layout: None,
+            on_caption_button_event: None,
+            caption_reveal: None,
+            caption_double_click: CaptionAction::ToggleMaximize,
+            caption_middle_click: None,
+            caption_drag: CaptionDrag::Immediate,
+            tab_strip: None,
+            on_tab_strip_layout: None,
+            pointer_hit_targets: PointerHitTargets::default(),
         }
     }
     pub fn extended_custom_caption(extra_height: i32) -> Self {
@@ -59,11 +165,27 @@ impl WindowFrame {
             theme: None,
             extend_frame: Margins::extended_caption(extra_height),
             extend_client_area: Margins::default_caption(),
+            min_client_size: None,
+            max_client_size: None,
+            aspect_ratio: None,
+            resize_increment: None,
+            resize_base_size: Size::default(),
+            on_grid_resize: None,
+            snapping: None,
             hit_test_caption_buttons: true,
             hit_test_extended_caption: true,
             hit_test_extended_resize_borders: false,
             intercept_client_area_hit_test: None,
             intercept_top_resize_border_hit_test: None,
+            caption_button_layout: None,
+            on_caption_button_event: None,
+            caption_reveal: None,
+            caption_double_click: CaptionAction::ToggleMaximize,
+            caption_middle_click: None,
+            caption_drag: CaptionDrag::Immediate,
+            tab_strip: None,
+            on_tab_strip_layout: None,
+            pointer_hit_targets: PointerHitTargets::default(),
         }
     }
     pub fn custom_sheet() -> Self {
@@ -71,11 +193,27 @@ impl WindowFrame {
             theme: None,
             extend_frame: Margins::sheet(),
             extend_client_area: Margins::default_caption(),
+            min_client_size: None,
+            max_client_size: None,
+            aspect_ratio: None,
+            resize_increment: None,
+            resize_base_size: Size::default(),
+            on_grid_resize: None,
+            snapping: None,
             hit_test_caption_buttons: true,
             hit_test_extended_caption: true,
             hit_test_extended_resize_borders: false,
             intercept_client_area_hit_test: None,
             intercept_top_resize_border_hit_test: None,
+            caption_button_layout: None,
+            on_caption_button_event: None,
+            caption_reveal: None,
+            caption_double_click: CaptionAction::ToggleMaximize,
+            caption_middle_click: None,
+            caption_drag: CaptionDrag::Immediate,
+            tab_strip: None,
+            on_tab_strip_layout: None,
+            pointer_hit_targets: PointerHitTargets::default(),
         }
     }
     pub fn custom_caption_height(caption_height: i32) -> Self {
@@ -83,11 +221,27 @@ impl WindowFrame {
             theme: None,
             extend_frame: Margins::caption(caption_height),
             extend_client_area: Margins::default_caption(),
+            min_client_size: None,
+            max_client_size: None,
+            aspect_ratio: None,
+            resize_increment: None,
+            resize_base_size: Size::default(),
+            on_grid_resize: None,
+            snapping: None,
             hit_test_caption_buttons: true,
             hit_test_extended_caption: true,
             hit_test_extended_resize_borders: false,
             intercept_client_area_hit_test: None,
             intercept_top_resize_border_hit_test: None,
+            caption_button_layout: None,
+            on_caption_button_event: None,
+            caption_reveal: None,
+            caption_double_click: CaptionAction::ToggleMaximize,
+            caption_middle_click: None,
+            caption_drag: CaptionDrag::Immediate,
+            tab_strip: None,
+            on_tab_strip_layout: None,
+            pointer_hit_targets: PointerHitTargets::default(),
         }
     }
     pub fn remove_caption() -> Self {
@@ -95,11 +249,27 @@ impl WindowFrame {
             theme: None,
             extend_frame: Margins::default(),
             extend_client_area: Margins::default_caption(),
+            min_client_size: None,
+            max_client_size: None,
+            aspect_ratio: None,
+            resize_increment: None,
+            resize_base_size: Size::default(),
+            on_grid_resize: None,
+            snapping: None,
             hit_test_caption_buttons: true,
             hit_test_extended_caption: true,
             hit_test_extended_resize_borders: false,
             intercept_client_area_hit_test: None,
             intercept_top_resize_border_hit_test: None,
+            caption_button_layout: None,
+            on_caption_button_event: None,
+            caption_reveal: None,
+            caption_double_click: CaptionAction::ToggleMaximize,
+            caption_middle_click: None,
+            caption_drag: CaptionDrag::Immediate,
+            tab_strip: None,
+            on_tab_strip_layout: None,
+            pointer_hit_targets: PointerHitTargets::default(),
         }
     }
 }
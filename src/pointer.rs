@@ -0,0 +1,211 @@
+use {
+    crate::{
+        bindings::windows::win32::windows_and_messaging::WPARAM,
+        hit_test::{Border, Point, Rect},
+    },
+    windows::BOOL,
+    windows_dll::dll,
+};
+
+// Mouse messages synthesized from touch and pen input carry this in their extra info.
+const MI_WP_SIGNATURE: u32 = 0xff51_5700;
+const SIGNATURE_MASK: u32 = 0xffff_ff00;
+const TOUCH_FLAG: u32 = 0x80;
+
+const PT_TOUCH: u32 = 2;
+const PT_PEN: u32 = 3;
+const PT_MOUSE: u32 = 4;
+const PT_TOUCHPAD: u32 = 5;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PointerType {
+    #[default]
+    Mouse,
+    Touch,
+    Pen,
+}
+
+/// Sizes of the resize and caption targets, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HitTargetSize {
+    /// How far in from each edge of the window resizing starts.
+    pub resize_border: i32,
+    /// How far in from each corner resizing goes both ways.
+    pub resize_grip: i32,
+    /// How far down from the top of the window the caption reaches at least, over anything
+    /// but caption buttons and tabs. `0` keeps the caption as it is.
+    pub caption_height: i32,
+}
+
+/// Hit targets per kind of pointer. The system frame's borders are used for pointers
+/// without their own sizes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PointerHitTargets {
+    pub mouse: Option<HitTargetSize>,
+    pub touch: Option<HitTargetSize>,
+    pub pen: Option<HitTargetSize>,
+}
+impl PointerHitTargets {
+    /// Sizes that are comfortable to hit with a finger or a pen.
+    pub fn touch_friendly() -> Self {
+        Self {
+            mouse: None,
+            touch: Some(HitTargetSize {
+                resize_border: 20,
+                resize_grip: 40,
+                caption_height: 48,
+            }),
+            pen: Some(HitTargetSize {
+                resize_border: 12,
+                resize_grip: 24,
+                caption_height: 0,
+            }),
+        }
+    }
+    pub fn get(&self, pointer_type: PointerType) -> Option<HitTargetSize> {
+        match pointer_type {
+            PointerType::Mouse => self.mouse,
+            PointerType::Touch => self.touch,
+            PointerType::Pen => self.pen,
+        }
+    }
+}
+
+#[dll(user32)]
+extern "system" {
+    #[allow(non_snake_case)]
+    fn GetPointerType(pointer_id: u32, pointer_type: *mut u32) -> BOOL;
+}
+
+/// Tells touch and pen apart from the extra info of a mouse message.
+pub(crate) fn from_message_extra_info(extra_info: u32) -> Option<PointerType> {
+    if extra_info & SIGNATURE_MASK != MI_WP_SIGNATURE {
+        None
+    } else if extra_info & TOUCH_FLAG != 0 {
+        Some(PointerType::Touch)
+    } else {
+        Some(PointerType::Pen)
+    }
+}
+
+/// The pointer type of a `WM_POINTER*` or `WM_NCPOINTER*` message.
+pub(crate) unsafe fn from_pointer_message(w_param: WPARAM) -> Option<PointerType> {
+    if !GetPointerType::exists() {
+        return None;
+    }
+    let pointer_id = (w_param.0 & 0xffff) as u32;
+    let mut pointer_type = 0;
+    if !GetPointerType(pointer_id, &mut pointer_type).as_bool() {
+        return None;
+    }
+    match pointer_type {
+        PT_TOUCH => Some(PointerType::Touch),
+        PT_PEN => Some(PointerType::Pen),
+        PT_MOUSE | PT_TOUCHPAD => Some(PointerType::Mouse),
+        _ => None,
+    }
+}
+
+/// Which border `point` resizes when the targets are `targets` wide, in screen
+/// coordinates.
+pub(crate) fn resize_hit_test(
+    point: Point,
+    window: Rect,
+    targets: &HitTargetSize,
+) -> Option<Border> {
+    if !window.contains(point) {
+        return None;
+    }
+    let near = |distance: i32, size: i32| distance < size;
+    let left = point.x - window.left;
+    let right = window.right - 1 - point.x;
+    let top = point.y - window.top;
+    let bottom = window.bottom - 1 - point.y;
+
+    let grip = targets.resize_grip;
+    let border = targets.resize_border;
+    let is_left = near(left, border) || (near(left, grip) && near(top.min(bottom), border));
+    let is_right = near(right, border) || (near(right, grip) && near(top.min(bottom), border));
+    let is_top = near(top, border) || (near(top, grip) && near(left.min(right), border));
+    let is_bottom = near(bottom, border) || (near(bottom, grip) && near(left.min(right), border));
+
+    match (is_left, is_right, is_top, is_bottom) {
+        (true, _, true, _) => Some(Border::TopLeft),
+        (_, true, true, _) => Some(Border::TopRight),
+        (true, _, _, true) => Some(Border::BottomLeft),
+        (_, true, _, true) => Some(Border::BottomRight),
+        (true, _, _, _) => Some(Border::Left),
+        (_, true, _, _) => Some(Border::Right),
+        (_, _, true, _) => Some(Border::Top),
+        (_, _, _, true) => Some(Border::Bottom),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Rect = Rect {
+        left: 0,
+        top: 0,
+        right: 400,
+        bottom: 300,
+    };
+    const TARGETS: HitTargetSize = HitTargetSize {
+        resize_border: 10,
+        resize_grip: 30,
+        caption_height: 0,
+    };
+
+    fn hit_test(x: i32, y: i32) -> Option<Border> {
+        resize_hit_test(Point { x, y }, WINDOW, &TARGETS)
+    }
+
+    #[test]
+    fn corners() {
+        assert_eq!(hit_test(0, 0), Some(Border::TopLeft));
+        assert_eq!(hit_test(399, 0), Some(Border::TopRight));
+        assert_eq!(hit_test(0, 299), Some(Border::BottomLeft));
+        assert_eq!(hit_test(399, 299), Some(Border::BottomRight));
+    }
+
+    #[test]
+    fn grips_reach_along_the_edges() {
+        assert_eq!(hit_test(29, 5), Some(Border::TopLeft));
+        assert_eq!(hit_test(5, 29), Some(Border::TopLeft));
+        assert_eq!(hit_test(370, 294), Some(Border::BottomRight));
+        assert_eq!(hit_test(30, 5), Some(Border::Top));
+        assert_eq!(hit_test(5, 30), Some(Border::Left));
+        assert_eq!(hit_test(29, 10), None);
+    }
+
+    #[test]
+    fn edges() {
+        assert_eq!(hit_test(200, 0), Some(Border::Top));
+        assert_eq!(hit_test(200, 299), Some(Border::Bottom));
+        assert_eq!(hit_test(0, 150), Some(Border::Left));
+        assert_eq!(hit_test(390, 150), Some(Border::Right));
+        assert_eq!(hit_test(389, 150), None);
+        assert_eq!(hit_test(200, 150), None);
+    }
+
+    #[test]
+    fn outside_the_window() {
+        assert_eq!(hit_test(-1, 150), None);
+        assert_eq!(hit_test(400, 150), None);
+        assert_eq!(hit_test(200, 300), None);
+    }
+
+    #[test]
+    fn extra_info_of_synthesized_mouse_messages() {
+        assert_eq!(
+            from_message_extra_info(0xff51_5780),
+            Some(PointerType::Touch)
+        );
+        assert_eq!(from_message_extra_info(0xff51_5700), Some(PointerType::Pen));
+        assert_eq!(from_message_extra_info(0xff51_5701), Some(PointerType::Pen));
+        assert_eq!(from_message_extra_info(0), None);
+        assert_eq!(from_message_extra_info(0x1234_5680), None);
+    }
+}
@@ -0,0 +1,238 @@
+use std::time::{Duration, Instant};
+
+/// Slides the caption into view while in fullscreen, when the cursor touches the top edge
+/// of the screen.
+pub struct CaptionReveal {
+    /// Height of the strip at the top edge that reveals the caption.
+    pub trigger_height: i32,
+    /// Height of the revealed caption, which keeps it revealed while hovered.
+    pub caption_height: i32,
+    pub reveal_delay: Duration,
+    pub hide_delay: Duration,
+    pub on_event: Option<Box<dyn Fn(RevealEvent)>>,
+}
+impl CaptionReveal {
+    pub fn new(caption_height: i32) -> Self {
+        Self {
+            trigger_height: 2,
+            caption_height,
+            reveal_delay: Duration::from_millis(150),
+            hide_delay: Duration::from_millis(750),
+            on_event: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevealEvent {
+    Shown,
+    Hidden,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Hidden,
+    Arming { since: Instant },
+    Revealed,
+    Hiding { since: Instant },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RevealTracker {
+    phase: Phase,
+}
+impl Default for RevealTracker {
+    fn default() -> Self {
+        Self {
+            phase: Phase::Hidden,
+        }
+    }
+}
+impl RevealTracker {
+    pub(crate) fn is_revealed(&self) -> bool {
+        matches!(self.phase, Phase::Revealed | Phase::Hiding { .. })
+    }
+    pub(crate) fn is_idle(&self) -> bool {
+        matches!(self.phase, Phase::Hidden)
+    }
+    // `y` is relative to the top of the screen.
+    pub(crate) fn pointer_moved(
+        &mut self,
+        y: i32,
+        now: Instant,
+        config: &CaptionReveal,
+    ) -> Option<RevealEvent> {
+        if let Some(event) = self.tick(now, config) {
+            return Some(event);
+        }
+        match self.phase {
+            Phase::Hidden if y < config.trigger_height => {
+                self.phase = Phase::Arming { since: now };
+                self.tick(now, config)
+            }
+            Phase::Arming { .. } if y >= config.trigger_height => {
+                self.phase = Phase::Hidden;
+                None
+            }
+            Phase::Revealed if y >= config.caption_height => {
+                self.phase = Phase::Hiding { since: now };
+                self.tick(now, config)
+            }
+            Phase::Hiding { .. } if y < config.caption_height => {
+                self.phase = Phase::Revealed;
+                None
+            }
+            _ => None,
+        }
+    }
+    pub(crate) fn tick(&mut self, now: Instant, config: &CaptionReveal) -> Option<RevealEvent> {
+        match self.phase {
+            Phase::Arming { since } if now.duration_since(since) >= config.reveal_delay => {
+                self.phase = Phase::Revealed;
+                Some(RevealEvent::Shown)
+            }
+            Phase::Hiding { since } if now.duration_since(since) >= config.hide_delay => {
+                self.phase = Phase::Hidden;
+                Some(RevealEvent::Hidden)
+            }
+            _ => None,
+        }
+    }
+    pub(crate) fn reset(&mut self) -> Option<RevealEvent> {
+        let was_revealed = self.is_revealed();
+        self.phase = Phase::Hidden;
+        if was_revealed {
+            Some(RevealEvent::Hidden)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn reveals_after_the_delay_at_the_top_edge() {
+        let config = CaptionReveal::new(32);
+        let start = Instant::now();
+        let mut tracker = RevealTracker::default();
+
+        assert_eq!(tracker.pointer_moved(0, start, &config), None);
+        assert!(!tracker.is_revealed());
+        assert_eq!(tracker.tick(ms(start, 149), &config), None);
+        assert_eq!(
+            tracker.tick(ms(start, 150), &config),
+            Some(RevealEvent::Shown)
+        );
+        assert!(tracker.is_revealed());
+    }
+
+    #[test]
+    fn leaving_the_edge_before_the_delay_disarms() {
+        let config = CaptionReveal::new(32);
+        let start = Instant::now();
+        let mut tracker = RevealTracker::default();
+
+        tracker.pointer_moved(1, start, &config);
+        assert_eq!(tracker.pointer_moved(10, ms(start, 100), &config), None);
+        assert_eq!(tracker.tick(ms(start, 500), &config), None);
+        assert!(tracker.is_idle());
+    }
+
+    #[test]
+    fn below_the_trigger_height_does_nothing() {
+        let config = CaptionReveal::new(32);
+        let start = Instant::now();
+        let mut tracker = RevealTracker::default();
+
+        assert_eq!(tracker.pointer_moved(2, start, &config), None);
+        assert!(tracker.is_idle());
+    }
+
+    #[test]
+    fn hides_after_the_delay_below_the_caption() {
+        let config = CaptionReveal::new(32);
+        let start = Instant::now();
+        let mut tracker = RevealTracker::default();
+        tracker.pointer_moved(0, start, &config);
+        tracker.tick(ms(start, 150), &config);
+
+        // Hovering the revealed caption keeps it revealed.
+        assert_eq!(tracker.pointer_moved(31, ms(start, 1000), &config), None);
+        assert_eq!(tracker.tick(ms(start, 5000), &config), None);
+
+        assert_eq!(tracker.pointer_moved(32, ms(start, 5000), &config), None);
+        assert!(tracker.is_revealed());
+        assert_eq!(tracker.tick(ms(start, 5749), &config), None);
+        assert_eq!(
+            tracker.tick(ms(start, 5750), &config),
+            Some(RevealEvent::Hidden)
+        );
+        assert!(tracker.is_idle());
+    }
+
+    #[test]
+    fn returning_to_the_caption_cancels_hiding() {
+        let config = CaptionReveal::new(32);
+        let start = Instant::now();
+        let mut tracker = RevealTracker::default();
+        tracker.pointer_moved(0, start, &config);
+        tracker.tick(ms(start, 150), &config);
+
+        tracker.pointer_moved(100, ms(start, 200), &config);
+        assert_eq!(tracker.pointer_moved(10, ms(start, 500), &config), None);
+        assert_eq!(tracker.tick(ms(start, 2000), &config), None);
+        assert!(tracker.is_revealed());
+    }
+
+    #[test]
+    fn a_late_move_reports_the_pending_transition_first() {
+        let config = CaptionReveal::new(32);
+        let start = Instant::now();
+        let mut tracker = RevealTracker::default();
+        tracker.pointer_moved(0, start, &config);
+
+        assert_eq!(
+            tracker.pointer_moved(0, ms(start, 400), &config),
+            Some(RevealEvent::Shown)
+        );
+    }
+
+    #[test]
+    fn zero_delays_transition_immediately() {
+        let mut config = CaptionReveal::new(32);
+        config.reveal_delay = Duration::from_millis(0);
+        config.hide_delay = Duration::from_millis(0);
+        let start = Instant::now();
+        let mut tracker = RevealTracker::default();
+
+        assert_eq!(
+            tracker.pointer_moved(0, start, &config),
+            Some(RevealEvent::Shown)
+        );
+        assert_eq!(
+            tracker.pointer_moved(50, start, &config),
+            Some(RevealEvent::Hidden)
+        );
+    }
+
+    #[test]
+    fn reset_reports_hidden_only_when_revealed() {
+        let config = CaptionReveal::new(32);
+        let start = Instant::now();
+        let mut tracker = RevealTracker::default();
+        tracker.pointer_moved(0, start, &config);
+        assert_eq!(tracker.reset(), None);
+
+        tracker.pointer_moved(0, start, &config);
+        tracker.tick(ms(start, 150), &config);
+        assert_eq!(tracker.reset(), Some(RevealEvent::Hidden));
+        assert!(tracker.is_idle());
+    }
+}
@@ -0,0 +1,337 @@
+use crate::{
+    bindings::windows::win32::windows_and_messaging::LPARAM,
+    hit_test::{hi_word, lo_word, Border, Rect, Size, WindowFrameMetrics},
+    options::{Margins, WindowFrame},
+};
+
+// Everything around the window content: the remaining non-client area plus the frame
+// extended into the client area.
+unsafe fn content_frame(options: &WindowFrame) -> Margins {
+    let non_client = WindowFrameMetrics::new().non_client_margins(&options.extend_client_area);
+    let extend_frame = options.extend_frame.zero_if_sheet();
+    Margins {
+        left: non_client.left + extend_frame.left,
+        top: non_client.top + extend_frame.top,
+        right: non_client.right + extend_frame.right,
+        bottom: non_client.bottom + extend_frame.bottom,
+    }
+}
+
+pub(crate) unsafe fn adjust_sizing_rect(
+    rect: Rect,
+    border: Border,
+    options: &WindowFrame,
+) -> Option<Rect> {
+    let mut adjusted = None;
+    if let Some(aspect_ratio) = options.aspect_ratio.filter(|ratio| *ratio > 0.0) {
+        let frame = content_frame(options);
+        adjusted = Some(constrain_aspect_ratio(
+            adjusted.unwrap_or(rect),
+            border,
+            aspect_ratio,
+            &frame,
+        ));
+    }
+    if let Some(increment) = options.resize_increment {
+        let non_client = WindowFrameMetrics::new().non_client_margins(&options.extend_client_area);
+        adjusted = Some(snap_to_increment(
+            adjusted.unwrap_or(rect),
+            border,
+            increment,
+            options.resize_base_size,
+            &non_client,
+        ));
+    }
+    adjusted
+}
+
+pub(crate) fn constrain_aspect_ratio(
+    rect: Rect,
+    border: Border,
+    aspect_ratio: f64,
+    frame: &Margins,
+) -> Rect {
+    let frame_width = frame.left + frame.right;
+    let frame_height = frame.top + frame.bottom;
+    let content_width = (rect.width() - frame_width).max(0);
+    let content_height = (rect.height() - frame_height).max(0);
+
+    let width_for = |height: i32| (height as f64 * aspect_ratio).round() as i32;
+    let height_for = |width: i32| (width as f64 / aspect_ratio).round() as i32;
+
+    let (content_width, content_height) = match border {
+        Border::Left | Border::Right => (content_width, height_for(content_width)),
+        Border::Top | Border::Bottom => (width_for(content_height), content_height),
+        _ => {
+            // Follow whichever side of the corner was dragged further.
+            if width_for(content_height) > content_width {
+                (width_for(content_height), content_height)
+            } else {
+                (content_width, height_for(content_width))
+            }
+        }
+    };
+    resize_from(
+        rect,
+        border,
+        content_width + frame_width,
+        content_height + frame_height,
+    )
+}
+
+pub(crate) fn snap_to_increment(
+    rect: Rect,
+    border: Border,
+    increment: Size,
+    base: Size,
+    non_client: &Margins,
+) -> Rect {
+    let frame_width = non_client.left + non_client.right;
+    let frame_height = non_client.top + non_client.bottom;
+    let client = Size {
+        width: rect.width() - frame_width,
+        height: rect.height() - frame_height,
+    };
+    let grid = grid_size(client, increment, base);
+
+    let width = if border.is_left() || border.is_right() {
+        base.width + grid.width * increment.width.max(1) + frame_width
+    } else {
+        rect.width()
+    };
+    let height = if border.is_top() || border.is_bottom() {
+        base.height + grid.height * increment.height.max(1) + frame_height
+    } else {
+        rect.height()
+    };
+    resize_from(rect, border, width, height)
+}
+
+// The number of whole increments that fit in the client area past the base size.
+pub(crate) fn grid_size(client: Size, increment: Size, base: Size) -> Size {
+    Size {
+        width: ((client.width - base.width) / increment.width.max(1)).max(0),
+        height: ((client.height - base.height) / increment.height.max(1)).max(0),
+    }
+}
+
+pub(crate) fn client_size_from_l_param(l_param: LPARAM) -> Size {
+    Size {
+        width: lo_word(l_param.0 as u32) as i32,
+        height: hi_word(l_param.0 as u32) as i32,
+    }
+}
+
+// Resizes `rect` while keeping the edges opposite to `border` in place.
+pub(crate) fn resize_from(rect: Rect, border: Border, width: i32, height: i32) -> Rect {
+    let (left, right) = if border.is_left() {
+        (rect.right - width, rect.right)
+    } else {
+        (rect.left, rect.left + width)
+    };
+    let (top, bottom) = if border.is_top() {
+        (rect.bottom - height, rect.bottom)
+    } else {
+        (rect.top, rect.top + height)
+    };
+    Rect {
+        left,
+        top,
+        right,
+        bottom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> Rect {
+        Rect {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    #[test]
+    fn aspect_ratio_keeps_the_opposite_edges_for_every_border() {
+        let frame = Margins::default();
+        let cases = [
+            (Border::Left, rect(100, 0, 500, 100), rect(100, 0, 500, 200)),
+            (Border::Right, rect(0, 0, 400, 100), rect(0, 0, 400, 200)),
+            (Border::Top, rect(0, 100, 100, 400), rect(0, 100, 600, 400)),
+            (Border::Bottom, rect(0, 0, 100, 300), rect(0, 0, 600, 300)),
+            (
+                Border::TopLeft,
+                rect(0, 0, 800, 300),
+                rect(0, -100, 800, 300),
+            ),
+            (
+                Border::TopRight,
+                rect(10, 20, 410, 320),
+                rect(10, 20, 610, 320),
+            ),
+            (
+                Border::BottomLeft,
+                rect(10, 20, 410, 320),
+                rect(-190, 20, 410, 320),
+            ),
+            (
+                Border::BottomRight,
+                rect(0, 0, 800, 300),
+                rect(0, 0, 800, 400),
+            ),
+        ];
+        for (border, input, expected) in cases.iter().copied() {
+            assert_eq!(
+                constrain_aspect_ratio(input, border, 2.0, &frame),
+                expected,
+                "{:?}",
+                border
+            );
+        }
+    }
+
+    #[test]
+    fn aspect_ratio_excludes_the_frame() {
+        let frame = Margins {
+            left: 8,
+            top: 31,
+            right: 8,
+            bottom: 8,
+        };
+        assert_eq!(
+            constrain_aspect_ratio(rect(0, 0, 416, 100), Border::Right, 2.0, &frame),
+            rect(0, 0, 416, 239)
+        );
+    }
+
+    #[test]
+    fn aspect_ratio_clamps_to_the_frame() {
+        let frame = Margins {
+            left: 8,
+            top: 31,
+            right: 8,
+            bottom: 8,
+        };
+        assert_eq!(
+            constrain_aspect_ratio(rect(0, 0, 10, 10), Border::BottomRight, 2.0, &frame),
+            rect(0, 0, 16, 39)
+        );
+    }
+
+    #[test]
+    fn increments_snap_only_the_dragged_axes_for_every_border() {
+        let increment = Size {
+            width: 10,
+            height: 20,
+        };
+        let base = Size::default();
+        let non_client = Margins::default();
+        let cases = [
+            (Border::Left, rect(5, 0, 100, 47), rect(10, 0, 100, 47)),
+            (Border::Right, rect(0, 0, 95, 47), rect(0, 0, 90, 47)),
+            (Border::Top, rect(0, 3, 95, 50), rect(0, 10, 95, 50)),
+            (Border::Bottom, rect(0, 0, 95, 47), rect(0, 0, 95, 40)),
+            (Border::TopLeft, rect(5, 3, 100, 50), rect(10, 10, 100, 50)),
+            (Border::TopRight, rect(5, 3, 100, 50), rect(5, 10, 95, 50)),
+            (
+                Border::BottomLeft,
+                rect(5, 3, 100, 50),
+                rect(10, 3, 100, 43),
+            ),
+            (Border::BottomRight, rect(0, 0, 95, 47), rect(0, 0, 90, 40)),
+        ];
+        for (border, input, expected) in cases.iter().copied() {
+            assert_eq!(
+                snap_to_increment(input, border, increment, base, &non_client),
+                expected,
+                "{:?}",
+                border
+            );
+        }
+    }
+
+    #[test]
+    fn increments_start_from_the_base_size_and_frame() {
+        let increment = Size {
+            width: 10,
+            height: 10,
+        };
+        let base = Size {
+            width: 15,
+            height: 5,
+        };
+        let non_client = Margins {
+            left: 1,
+            top: 20,
+            right: 1,
+            bottom: 1,
+        };
+        assert_eq!(
+            snap_to_increment(
+                rect(0, 0, 60, 60),
+                Border::BottomRight,
+                increment,
+                base,
+                &non_client
+            ),
+            rect(0, 0, 57, 56)
+        );
+        // Smaller than the base size clamps to the base size.
+        assert_eq!(
+            snap_to_increment(
+                rect(0, 0, 5, 5),
+                Border::BottomRight,
+                increment,
+                base,
+                &non_client
+            ),
+            rect(0, 0, 17, 26)
+        );
+    }
+
+    #[test]
+    fn grid_size_clamps_and_ignores_zero_increments() {
+        let base = Size::default();
+        assert_eq!(
+            grid_size(
+                Size {
+                    width: 95,
+                    height: 47
+                },
+                Size {
+                    width: 10,
+                    height: 20
+                },
+                base
+            ),
+            Size {
+                width: 9,
+                height: 2
+            }
+        );
+        assert_eq!(
+            grid_size(
+                Size {
+                    width: 5,
+                    height: 5
+                },
+                Size {
+                    width: 0,
+                    height: 0
+                },
+                Size {
+                    width: 10,
+                    height: 2
+                }
+            ),
+            Size {
+                width: 0,
+                height: 3
+            }
+        );
+    }
+}
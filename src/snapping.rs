@@ -0,0 +1,215 @@
+use {
+    crate::{
+        bindings::windows::win32::{
+            gdi::{GetMonitorInfoW, MonitorFromRect, MONITORINFO},
+            system_services::MONITOR_DEFAULTTONEAREST,
+            windows_and_messaging::{IsIconic, IsWindowVisible, HWND},
+        },
+        hit_test::Rect,
+        util::{invisible_borders, visible_window_rect},
+        window_source::WindowSource,
+    },
+    std::{cell::RefCell, rc::Rc},
+};
+
+pub struct Snapping {
+    /// How close an edge has to get before it snaps, in pixels.
+    pub distance: i32,
+    pub snap_to_monitor: bool,
+    pub group: Option<SnapGroup>,
+}
+impl Snapping {
+    pub fn new(distance: i32) -> Self {
+        Self {
+            distance,
+            snap_to_monitor: true,
+            group: None,
+        }
+    }
+}
+
+/// Windows that dock to each other while being moved. Clones share the same group.
+#[derive(Debug, Default, Clone)]
+pub struct SnapGroup {
+    windows: Rc<RefCell<Vec<isize>>>,
+}
+impl SnapGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn add<M, W: WindowSource<M>>(&self, window: &W) {
+        let hwnd = window.hwnd();
+        let mut windows = self.windows.borrow_mut();
+        if !windows.contains(&hwnd) {
+            windows.push(hwnd);
+        }
+    }
+    pub fn remove<M, W: WindowSource<M>>(&self, window: &W) {
+        let hwnd = window.hwnd();
+        self.windows.borrow_mut().retain(|window| *window != hwnd);
+    }
+    unsafe fn rects_except(&self, h_wnd: HWND) -> Vec<Rect> {
+        self.windows
+            .borrow()
+            .iter()
+            .map(|hwnd| HWND(*hwnd))
+            .filter(|hwnd| {
+                *hwnd != h_wnd && IsWindowVisible(*hwnd).as_bool() && !IsIconic(*hwnd).as_bool()
+            })
+            .map(|hwnd| visible_window_rect(hwnd))
+            .collect()
+    }
+}
+
+pub(crate) unsafe fn snap_moving_rect(h_wnd: HWND, rect: Rect, snapping: &Snapping) -> Rect {
+    let mut monitors = Vec::new();
+    if snapping.snap_to_monitor {
+        let monitor = MonitorFromRect(&rect.to_win32(), MONITOR_DEFAULTTONEAREST as _);
+        let mut monitor_info = MONITORINFO {
+            cb_size: std::mem::size_of::<MONITORINFO>() as _,
+            ..Default::default()
+        };
+        if GetMonitorInfoW(monitor, &mut monitor_info).as_bool() {
+            monitors.push(Rect::from_win32(&monitor_info.rc_work));
+        }
+    }
+    let windows = match &snapping.group {
+        Some(group) => group.rects_except(h_wnd),
+        None => Vec::new(),
+    };
+    // Line up what's visible, not the invisible resize borders around it.
+    let borders = invisible_borders(h_wnd);
+    let visible = Rect {
+        left: rect.left + borders.left,
+        top: rect.top + borders.top,
+        right: rect.right - borders.right,
+        bottom: rect.bottom - borders.bottom,
+    };
+    let snapped = snap_rect(visible, &monitors, &windows, snapping.distance);
+    let dx = snapped.left - visible.left;
+    let dy = snapped.top - visible.top;
+    Rect {
+        left: rect.left + dx,
+        top: rect.top + dy,
+        right: rect.right + dx,
+        bottom: rect.bottom + dy,
+    }
+}
+
+/// Moves `rect` so its edges line up with the closest monitor or window edge within
+/// `distance`, keeping its size.
+pub(crate) fn snap_rect(rect: Rect, monitors: &[Rect], windows: &[Rect], distance: i32) -> Rect {
+    let mut vertical_lines = Vec::new();
+    let mut horizontal_lines = Vec::new();
+    for monitor in monitors {
+        vertical_lines.extend_from_slice(&[monitor.left, monitor.right]);
+        horizontal_lines.extend_from_slice(&[monitor.top, monitor.bottom]);
+    }
+    for window in windows {
+        // Only dock to edges that are close enough to touch.
+        if overlaps(rect.top, rect.bottom, window.top, window.bottom, distance) {
+            vertical_lines.extend_from_slice(&[window.left, window.right]);
+        }
+        if overlaps(rect.left, rect.right, window.left, window.right, distance) {
+            horizontal_lines.extend_from_slice(&[window.top, window.bottom]);
+        }
+    }
+
+    let dx = snap_offset(rect.left, rect.right, &vertical_lines, distance);
+    let dy = snap_offset(rect.top, rect.bottom, &horizontal_lines, distance);
+    Rect {
+        left: rect.left + dx,
+        top: rect.top + dy,
+        right: rect.right + dx,
+        bottom: rect.bottom + dy,
+    }
+}
+
+fn overlaps(start: i32, end: i32, other_start: i32, other_end: i32, distance: i32) -> bool {
+    start < other_end + distance && other_start < end + distance
+}
+
+// The smallest offset that puts either edge on one of the lines.
+fn snap_offset(start: i32, end: i32, lines: &[i32], distance: i32) -> i32 {
+    let start_offsets = lines.iter().map(|line| line - start);
+    let end_offsets = lines.iter().map(|line| line - end);
+    start_offsets
+        .chain(end_offsets)
+        .filter(|offset| offset.abs() <= distance)
+        .min_by_key(|offset| offset.abs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(left: i32, top: i32, right: i32, bottom: i32) -> Rect {
+        Rect {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    const WORK_AREA: Rect = Rect {
+        left: 0,
+        top: 0,
+        right: 1920,
+        bottom: 1040,
+    };
+
+    #[test]
+    fn snaps_to_monitor_edges_within_distance() {
+        let monitors = [WORK_AREA];
+        assert_eq!(
+            snap_rect(rect(5, 100, 405, 400), &monitors, &[], 10),
+            rect(0, 100, 400, 400)
+        );
+        assert_eq!(
+            snap_rect(rect(1515, 700, 1915, 1043), &monitors, &[], 10),
+            rect(1520, 697, 1920, 1040)
+        );
+        assert_eq!(
+            snap_rect(rect(11, 100, 411, 400), &monitors, &[], 10),
+            rect(11, 100, 411, 400)
+        );
+    }
+
+    #[test]
+    fn docks_to_windows_beside_it() {
+        let windows = [rect(500, 100, 900, 400)];
+        assert_eq!(
+            snap_rect(rect(905, 120, 1205, 420), &[], &windows, 10),
+            rect(900, 120, 1200, 420)
+        );
+        assert_eq!(
+            snap_rect(rect(600, 392, 1000, 600), &[], &windows, 10),
+            rect(600, 400, 1000, 608)
+        );
+    }
+
+    #[test]
+    fn ignores_windows_too_far_along_the_other_axis() {
+        let windows = [rect(500, 1000, 900, 1200)];
+        assert_eq!(
+            snap_rect(rect(905, 120, 1205, 420), &[], &windows, 10),
+            rect(905, 120, 1205, 420)
+        );
+    }
+
+    #[test]
+    fn picks_the_closest_line() {
+        let windows = [rect(0, 0, 103, 400)];
+        assert_eq!(
+            snap_rect(
+                rect(101, 0, 501, 400),
+                &[rect(0, 0, 1000, 1000)],
+                &windows,
+                10
+            ),
+            rect(103, 0, 503, 400)
+        );
+    }
+}
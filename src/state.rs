@@ -0,0 +1,43 @@
+use crate::bindings::windows::win32::{
+    system_services::{SIZE_MAXIMIZED, SIZE_MINIMIZED, WA_INACTIVE},
+    windows_and_messaging::{GetForegroundWindow, IsIconic, IsZoomed, HWND, WPARAM},
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WindowState {
+    pub maximized: bool,
+    pub minimized: bool,
+    pub fullscreen: bool,
+    pub active: bool,
+}
+impl WindowState {
+    // Fullscreen only ever comes from `enter_fullscreen`, never from the window's geometry.
+    pub(crate) unsafe fn query(h_wnd: HWND, fullscreen: bool) -> Self {
+        Self {
+            maximized: IsZoomed(h_wnd).as_bool(),
+            minimized: IsIconic(h_wnd).as_bool(),
+            fullscreen,
+            active: GetForegroundWindow() == h_wnd,
+        }
+    }
+    pub fn is_resizable(&self) -> bool {
+        !(self.maximized || self.minimized || self.fullscreen)
+    }
+    pub(crate) fn on_size(&mut self, w_param: WPARAM) {
+        let kind = w_param.0 as u32;
+        self.maximized = kind == SIZE_MAXIMIZED as u32;
+        self.minimized = kind == SIZE_MINIMIZED as u32;
+    }
+    pub(crate) fn on_activate(&mut self, w_param: WPARAM) {
+        self.active = (w_param.0 & 0xffff) as u32 != WA_INACTIVE as u32;
+    }
+    pub(crate) unsafe fn on_window_pos_changed(&mut self, h_wnd: HWND) {
+        let Self {
+            active, fullscreen, ..
+        } = *self;
+        *self = Self {
+            active,
+            ..Self::query(h_wnd, fullscreen)
+        };
+    }
+}
@@ -1,15 +1,304 @@
-use crate::{
-    bindings::windows::win32::{
-        dwm::{DwmDefWindowProc, DwmExtendFrameIntoClientArea},
-        shell::DefSubclassProc,
-        system_services::{LRESULT, TRUE, WM_ACTIVATE, WM_NCCALCSIZE, WM_NCHITTEST},
-        windows_and_messaging::{HWND, LPARAM, WPARAM},
+use {
+    crate::{
+        bindings::windows::win32::{
+            display_devices::{POINT, RECT},
+            dwm::{DwmDefWindowProc, DwmExtendFrameIntoClientArea},
+            gdi::ClientToScreen,
+            keyboard_and_mouse_input::{
+                ReleaseCapture, SetCapture, TrackMouseEvent, TRACKMOUSEEVENT,
+            },
+            shell::DefSubclassProc,
+            system_services::{
+                HTCAPTION, HTCLIENT, LRESULT, MK_LBUTTON, SC_KEYMENU, TME_LEAVE, TME_NONCLIENT,
+                TRUE, WM_ACTIVATE, WM_CAPTURECHANGED, WM_DISPLAYCHANGE, WM_DPICHANGED,
+                WM_DWMCOMPOSITIONCHANGED, WM_GETMINMAXINFO, WM_GETOBJECT, WM_LBUTTONDBLCLK,
+                WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONUP, WM_MOUSEMOVE, WM_MOVING, WM_NCACTIVATE,
+                WM_NCCALCSIZE, WM_NCHITTEST, WM_NCLBUTTONDBLCLK, WM_NCLBUTTONDOWN, WM_NCMBUTTONUP,
+                WM_NCMOUSELEAVE, WM_NCMOUSEMOVE, WM_NCPAINT, WM_NCPOINTERDOWN, WM_NCPOINTERUPDATE,
+                WM_NCRBUTTONUP, WM_POINTERDOWN, WM_POINTERUPDATE, WM_RBUTTONUP, WM_SETTEXT,
+                WM_SIZE, WM_SIZING, WM_SYSCOMMAND, WM_TIMER, WM_WINDOWPOSCHANGED,
+            },
+            windows_and_messaging::{
+                GetCursorPos, GetMessageExtraInfo, GetWindowRect, KillTimer, SetTimer, HWND,
+                LPARAM, MINMAXINFO, WPARAM,
+            },
+        },
+        caption_buttons::{CaptionButton, CaptionButtonEvent, CaptionButtons},
+        command::{self, WindowCommand},
+        drag::{self, caption_press, drag_threshold, CaptionPress},
+        fallback::paint_non_client_frame,
+        fullscreen::fit_to_monitor,
+        hit_test::{
+            is_declared_caption, non_client_hit_test, transform_hit_test, Border, HitTestArea,
+            Point, Rect, Size, WindowMetrics,
+        },
+        layout::{CaptionButtonRect, CaptionMetrics},
+        min_max::adjust_min_max_info,
+        options::{CaptionAction, CaptionDrag, Margins, WindowFrame},
+        pointer::{self, PointerType},
+        reveal::{CaptionReveal, RevealEvent, RevealTracker},
+        sizing::{adjust_sizing_rect, client_size_from_l_param, grid_size},
+        snapping::snap_moving_rect,
+        state::WindowState,
+        tab_strip::{TabStrip, TabStripArea},
+        title_bar::{caption_rect, TitleBar},
+        uia::{return_root_provider, SharedTree, UIA_ROOT_OBJECT_ID},
+        util::{
+            client_size, frame_changed, is_dwm_enabled, visible_window_rect, window_text,
+            NCCALCSIZE_PARAMS,
+        },
+    },
+    std::{
+        cell::{Cell, RefCell},
+        sync::{Arc, Mutex},
+        time::Instant,
     },
-    hit_test::{non_client_hit_test, transform_hit_test, HitTestArea, Point, WindowMetrics},
-    options::WindowFrame,
-    util::{is_dwm_enabled, NCCALCSIZE_PARAMS},
 };
 
+pub(crate) struct SubclassData {
+    pub(crate) options: WindowFrame,
+    pub(crate) state: Cell<WindowState>,
+    pub(crate) fullscreen: Cell<bool>,
+    pub(crate) caption_reveal: Cell<RevealTracker>,
+    pub(crate) grid_size: Cell<Option<Size>>,
+    pub(crate) caption_buttons: Cell<CaptionButtons>,
+    // Laid out again on every `WM_SIZE`, so hit testing doesn't have to.
+    pub(crate) button_rects: RefCell<Vec<CaptionButtonRect>>,
+    pub(crate) tab_strip: RefCell<Option<TabStrip>>,
+    // Where a press on the caption went down, while it could still become a drag.
+    pub(crate) caption_press: Cell<Option<Point>>,
+    pub(crate) pointer_type: Cell<PointerType>,
+    // Only filled in once UI Automation has asked for the title bar.
+    pub(crate) accessibility: SharedTree,
+}
+impl SubclassData {
+    pub(crate) fn new(options: WindowFrame) -> Self {
+        Self {
+            options,
+            state: Cell::new(WindowState::default()),
+            fullscreen: Cell::new(false),
+            caption_reveal: Cell::new(RevealTracker::default()),
+            grid_size: Cell::new(None),
+            caption_buttons: Cell::new(CaptionButtons::new()),
+            button_rects: RefCell::new(Vec::new()),
+            tab_strip: RefCell::new(None),
+            caption_press: Cell::new(None),
+            pointer_type: Cell::new(PointerType::Mouse),
+            accessibility: Arc::new(Mutex::new(None)),
+        }
+    }
+    pub(crate) fn extend_frame(&self) -> Margins {
+        if self.fullscreen.get() {
+            Margins::default()
+        } else {
+            self.options.extend_frame.clone()
+        }
+    }
+    pub(crate) unsafe fn update_caption_reveal(
+        &self,
+        h_wnd: HWND,
+        reveal: &CaptionReveal,
+        update: impl FnOnce(&mut RevealTracker, Instant) -> Option<RevealEvent>,
+    ) {
+        let mut tracker = self.caption_reveal.get();
+        let was_idle = tracker.is_idle();
+        let event = update(&mut tracker, Instant::now());
+        self.caption_reveal.set(tracker);
+
+        // Keep polling while the caption is, or is about to be, revealed.
+        if was_idle && !tracker.is_idle() {
+            SetTimer(h_wnd, CAPTION_REVEAL_TIMER, CAPTION_REVEAL_POLL_MS, None);
+        } else if !was_idle && tracker.is_idle() {
+            KillTimer(h_wnd, CAPTION_REVEAL_TIMER);
+        }
+
+        if let (Some(event), Some(on_event)) = (event, &reveal.on_event) {
+            on_event(event);
+        }
+    }
+    pub(crate) unsafe fn update_caption_buttons(
+        &self,
+        h_wnd: HWND,
+        update: impl FnOnce(&mut CaptionButtons) -> Option<CaptionButtonEvent>,
+    ) {
+        let mut buttons = self.caption_buttons.get();
+        let event = update(&mut buttons);
+        self.caption_buttons.set(buttons);
+
+        if let Some(event) = event {
+            if let Some(on_event) = &self.options.on_caption_button_event {
+                on_event(event);
+            }
+            if let CaptionButtonEvent::Invoke(command) = event {
+                command::post(h_wnd, command);
+            }
+        }
+    }
+    pub(crate) fn update_state(&self, update: impl FnOnce(&mut WindowState)) {
+        let mut state = self.state.get();
+        update(&mut state);
+        self.state.set(state);
+    }
+    pub(crate) unsafe fn caption_button_rects(&self, h_wnd: HWND) -> Vec<CaptionButtonRect> {
+        match &self.options.caption_button_layout {
+            Some(layout) => {
+                let client_width = client_size(h_wnd).width;
+                let metrics = CaptionMetrics::window(h_wnd, &self.options.extend_client_area);
+                layout.layout(client_width, &metrics, self.state.get().maximized)
+            }
+            None => Vec::new(),
+        }
+    }
+    pub(crate) unsafe fn title_bar<'a>(&self, h_wnd: HWND, title: &'a str) -> TitleBar<'a> {
+        let options = &self.options;
+        let state = self.state.get();
+        let metrics = CaptionMetrics::window(h_wnd, &options.extend_client_area);
+        TitleBar {
+            title,
+            caption: caption_rect(client_size(h_wnd).width, options, &metrics, state.maximized),
+            active: state.active,
+            scale: options
+                .caption_button_layout
+                .as_ref()
+                .map(|layout| layout.scale)
+                .unwrap_or(1.0)
+                * metrics.scale,
+            buttons: self.caption_button_rects(h_wnd),
+            button_states: self.caption_buttons.get(),
+        }
+    }
+    // Rebuilds the snapshot read by UI Automation, unless nobody asked for it yet.
+    pub(crate) unsafe fn update_accessibility(&self, h_wnd: HWND, create: bool) {
+        if self.options.caption_button_layout.is_none() {
+            return;
+        }
+        if let Ok(mut tree) = self.accessibility.lock() {
+            if tree.is_some() || create {
+                let title = window_text(h_wnd);
+                *tree = Some(self.title_bar(h_wnd, &title).accessibility_tree());
+            }
+        }
+    }
+    pub(crate) unsafe fn tab_strip(&self, h_wnd: HWND) -> Option<TabStrip> {
+        let layout = self.options.tab_strip.as_ref()?;
+        let title_bar = self.title_bar(h_wnd, "");
+        Some(layout.layout(title_bar.caption, &title_bar.buttons))
+    }
+    pub(crate) unsafe fn update_layout(&self, h_wnd: HWND) {
+        self.button_rects.replace(self.caption_button_rects(h_wnd));
+        let tab_strip = self.tab_strip(h_wnd);
+        if *self.tab_strip.borrow() != tab_strip {
+            if let (Some(on_tab_strip_layout), Some(tab_strip)) =
+                (&self.options.on_tab_strip_layout, &tab_strip)
+            {
+                on_tab_strip_layout(tab_strip);
+            }
+            self.tab_strip.replace(tab_strip);
+        }
+    }
+}
+
+pub(crate) const CAPTION_REVEAL_TIMER: usize = 0x5752_4556;
+const CAPTION_REVEAL_POLL_MS: u32 = 50;
+
+unsafe fn hit_test_area(h_wnd: HWND, global_position: Point, data: &SubclassData) -> HitTestArea {
+    let options = &data.options;
+    let state = data.state.get();
+
+    let metrics = WindowMetrics::new(h_wnd);
+
+    let def_hit_test = non_client_hit_test(global_position, &metrics, options, &state);
+    let client_position = def_hit_test.client_position;
+    let area = transform_hit_test(
+        def_hit_test,
+        options,
+        &state,
+        &data.button_rects.borrow(),
+        data.tab_strip.borrow().as_ref(),
+    );
+
+    // Touch and pen get wider borders and a taller caption, over whatever isn't a caption
+    // button.
+    let targets = match options.pointer_hit_targets.get(data.pointer_type.get()) {
+        Some(targets) if matches!(area, HitTestArea::Caption | HitTestArea::Client) => targets,
+        _ => return area,
+    };
+    // Measured from the visible frame, the invisible borders are resize borders already.
+    let window = visible_window_rect(h_wnd);
+    if state.is_resizable() {
+        if let Some(border) = pointer::resize_hit_test(global_position, window, &targets) {
+            return HitTestArea::Resize(border);
+        }
+    }
+    let over_tab = matches!(
+        data.tab_strip
+            .borrow()
+            .as_ref()
+            .and_then(|tab_strip| tab_strip.hit_test(client_position)),
+        Some(TabStripArea::Tab(_))
+    );
+    if matches!(area, HitTestArea::Client)
+        && !over_tab
+        && global_position.y - window.top < targets.caption_height
+    {
+        return HitTestArea::Caption;
+    }
+    area
+}
+
+// Where a client area message landed, in screen coordinates, if that's on the caption.
+unsafe fn caption_position(h_wnd: HWND, l_param: LPARAM, data: &SubclassData) -> Option<Point> {
+    let mut global_position = Point::from_l_param(l_param).to_win32();
+    ClientToScreen(h_wnd, &mut global_position);
+    let global_position = Point::from_win32(&global_position);
+    match hit_test_area(h_wnd, global_position, data) {
+        HitTestArea::Caption => Some(global_position),
+        _ => None,
+    }
+}
+
+// Like `caption_position`, but only where the application declared the caption itself. The
+// rest of the extended caption can have its widgets on it, which need their clicks.
+unsafe fn declared_caption_position(
+    h_wnd: HWND,
+    l_param: LPARAM,
+    data: &SubclassData,
+) -> Option<Point> {
+    let global_position = caption_position(h_wnd, l_param, data)?;
+    let metrics = WindowMetrics::new(h_wnd);
+    let hit_test = non_client_hit_test(global_position, &metrics, &data.options, &data.state.get());
+    if is_declared_caption(&hit_test, &data.options, data.tab_strip.borrow().as_ref()) {
+        Some(global_position)
+    } else {
+        None
+    }
+}
+
+unsafe fn track_non_client_leave(h_wnd: HWND) {
+    let mut event = TRACKMOUSEEVENT {
+        cb_size: std::mem::size_of::<TRACKMOUSEEVENT>() as _,
+        dw_flags: (TME_LEAVE | TME_NONCLIENT) as _,
+        hwnd_track: h_wnd,
+        dw_hover_time: 0,
+    };
+    TrackMouseEvent(&mut event);
+}
+
+unsafe fn run_caption_action(h_wnd: HWND, action: &CaptionAction, position: Point) {
+    match action {
+        CaptionAction::Ignore => {}
+        CaptionAction::Minimize => command::post(h_wnd, WindowCommand::Minimize),
+        CaptionAction::ToggleMaximize => command::toggle_maximize(h_wnd),
+        CaptionAction::Callback(callback) => callback(position),
+    }
+}
+
+unsafe fn screen_top(h_wnd: HWND) -> i32 {
+    let mut rect = RECT::default();
+    GetWindowRect(h_wnd, &mut rect);
+    rect.top
+}
+
 pub(crate) extern "system" fn subclass_procedure(
     h_wnd: HWND,
     u_msg: u32,
@@ -19,56 +308,339 @@ pub(crate) extern "system" fn subclass_procedure(
     dw_ref_data: usize,
 ) -> LRESULT {
     unsafe {
-        if is_dwm_enabled() {
-            let options = &*(dw_ref_data as *const WindowFrame);
+        let data = &*(dw_ref_data as *const SubclassData);
+        let options = &data.options;
 
-            let msg = u_msg as i32;
+        let msg = u_msg as i32;
+        let dwm_enabled = is_dwm_enabled();
+        let fullscreen = data.fullscreen.get();
 
-            let (dwm_result, dwm_handled) = {
-                if options.hit_test_caption_buttons {
-                    let mut result = LRESULT(0);
-                    let handled =
-                        DwmDefWindowProc(h_wnd, u_msg, w_param, l_param, &mut result).is_ok();
-                    (result, handled)
-                } else {
-                    (LRESULT(0), false)
+        if msg == WM_SIZE {
+            data.update_state(|state| state.on_size(w_param));
+            let maximized = data.state.get().maximized;
+            data.update_caption_buttons(h_wnd, |buttons| buttons.set_maximized(maximized));
+
+            if let (Some(increment), Some(on_grid_resize)) =
+                (options.resize_increment, &options.on_grid_resize)
+            {
+                if !data.state.get().minimized {
+                    let client_size = client_size_from_l_param(l_param);
+                    let grid = grid_size(client_size, increment, options.resize_base_size);
+                    if data.grid_size.replace(Some(grid)) != Some(grid) {
+                        on_grid_resize(grid);
+                    }
                 }
-            };
+            }
+            data.update_layout(h_wnd);
+            data.update_accessibility(h_wnd, false);
+        }
+        if msg == WM_DPICHANGED {
+            // The buttons follow the new DPI even if the window keeps its size.
+            data.update_layout(h_wnd);
+            data.update_accessibility(h_wnd, false);
+        }
+        if msg == WM_SETTEXT {
+            let result = DefSubclassProc(h_wnd, u_msg, w_param, l_param);
+            data.update_accessibility(h_wnd, false);
+            return result;
+        }
+        if msg == WM_ACTIVATE {
+            data.update_state(|state| state.on_activate(w_param));
+        }
+        if msg == WM_WINDOWPOSCHANGED {
+            data.update_state(|state| state.on_window_pos_changed(h_wnd));
+        }
+        let state = data.state.get();
+
+        let (dwm_result, dwm_handled) = {
+            if dwm_enabled && !fullscreen && options.hit_test_caption_buttons {
+                let mut result = LRESULT(0);
+                let handled = DwmDefWindowProc(h_wnd, u_msg, w_param, l_param, &mut result).is_ok();
+                (result, handled)
+            } else {
+                (LRESULT(0), false)
+            }
+        };
 
-            if msg == WM_ACTIVATE {
-                // Extend the frame into the client area.
-                let p_mar_inset = options.extend_frame.to_win32();
+        if msg == WM_DWMCOMPOSITIONCHANGED {
+            if dwm_enabled {
+                let p_mar_inset = data.extend_frame().to_win32();
                 DwmExtendFrameIntoClientArea(h_wnd, &p_mar_inset);
             }
+            // Switch between the DWM frame and the fallback frame.
+            frame_changed(h_wnd);
+        }
+        if msg == WM_ACTIVATE && dwm_enabled {
+            // Extend the frame into the client area.
+            let p_mar_inset = data.extend_frame().to_win32();
+            DwmExtendFrameIntoClientArea(h_wnd, &p_mar_inset);
+        }
+        if fullscreen {
+            if msg == WM_DISPLAYCHANGE {
+                fit_to_monitor(h_wnd);
+            }
             if msg == WM_NCCALCSIZE && w_param == WPARAM(TRUE as _) {
-                let WindowFrame {
-                    extend_client_area: adjust_client_area,
-                    ..
-                } = options;
+                // The whole window is client area.
+                return LRESULT(0);
+            }
+            if let Some(reveal) = &options.caption_reveal {
+                if msg == WM_MOUSEMOVE {
+                    let y = Point::from_l_param(l_param).y;
+                    data.update_caption_reveal(h_wnd, reveal, |tracker, now| {
+                        tracker.pointer_moved(y, now, reveal)
+                    });
+                }
+                if msg == WM_TIMER && w_param.0 == CAPTION_REVEAL_TIMER {
+                    // Poll the cursor, it may have left the window without another message.
+                    let mut cursor = POINT::default();
+                    GetCursorPos(&mut cursor);
+                    let y = cursor.y - screen_top(h_wnd);
+                    data.update_caption_reveal(h_wnd, reveal, |tracker, now| {
+                        tracker.pointer_moved(y, now, reveal)
+                    });
+                    return LRESULT(0);
+                }
+                if msg == WM_NCHITTEST {
+                    let y = Point::from_l_param(l_param).y - screen_top(h_wnd);
+                    data.update_caption_reveal(h_wnd, reveal, |tracker, now| {
+                        tracker.pointer_moved(y, now, reveal)
+                    });
+                    if data.caption_reveal.get().is_revealed() && y < reveal.caption_height {
+                        return LRESULT(HTCAPTION);
+                    }
+                }
+            }
+            if msg == WM_NCHITTEST {
+                return LRESULT(HTCLIENT);
+            }
+        }
+        if msg == WM_GETMINMAXINFO && !fullscreen {
+            // Let the application set its own limits first, ours only ever tighten them.
+            let result = DefSubclassProc(h_wnd, u_msg, w_param, l_param);
+            let info = &mut *(l_param.0 as *mut MINMAXINFO);
+            adjust_min_max_info(h_wnd, info, options);
+            return result;
+        }
+        if msg == WM_MOVING && !fullscreen {
+            if let Some(snapping) = &options.snapping {
+                let rect = &mut *(l_param.0 as *mut RECT);
+                *rect = snap_moving_rect(h_wnd, Rect::from_win32(rect), snapping).to_win32();
+                return LRESULT(TRUE as _);
+            }
+        }
+        if msg == WM_SIZING && !fullscreen {
+            if let Some(border) = Border::from_sizing_edge(w_param) {
+                let rect = &mut *(l_param.0 as *mut RECT);
+                if let Some(adjusted) = adjust_sizing_rect(Rect::from_win32(rect), border, options)
+                {
+                    *rect = adjusted.to_win32();
+                    return LRESULT(TRUE as _);
+                }
+            }
+        }
+        if msg == WM_NCCALCSIZE && w_param == WPARAM(TRUE as _) {
+            let WindowFrame {
+                extend_client_area: adjust_client_area,
+                ..
+            } = options;
 
-                // Calculate new NCCALCSIZE_PARAMS based on custom NCA inset.
-                let pncsp = &mut *(l_param.0 as *mut NCCALCSIZE_PARAMS);
+            // Calculate new NCCALCSIZE_PARAMS based on custom NCA inset.
+            let pncsp = &mut *(l_param.0 as *mut NCCALCSIZE_PARAMS);
 
-                pncsp.rgrc[0].left -= adjust_client_area.left;
-                pncsp.rgrc[0].top -= adjust_client_area.top;
-                pncsp.rgrc[0].right += adjust_client_area.right;
-                pncsp.rgrc[0].bottom += adjust_client_area.bottom;
+            pncsp.rgrc[0].left -= adjust_client_area.left;
+            pncsp.rgrc[0].top -= adjust_client_area.top;
+            pncsp.rgrc[0].right += adjust_client_area.right;
+            pncsp.rgrc[0].bottom += adjust_client_area.bottom;
+        }
+        if msg == WM_NCPOINTERDOWN
+            || msg == WM_NCPOINTERUPDATE
+            || msg == WM_POINTERDOWN
+            || msg == WM_POINTERUPDATE
+        {
+            if let Some(pointer_type) = pointer::from_pointer_message(w_param) {
+                data.pointer_type.set(pointer_type);
             }
-            if msg == WM_NCHITTEST && !dwm_handled {
-                let global_position = Point::from_l_param(l_param);
-
-                let metrics = WindowMetrics::new(h_wnd);
+        }
+        if msg == WM_NCHITTEST {
+            // Mouse messages promoted from touch and pen are marked as such.
+            let extra_info = GetMessageExtraInfo().0 as u32;
+            if let Some(pointer_type) = pointer::from_message_extra_info(extra_info) {
+                data.pointer_type.set(pointer_type);
+            }
+        }
+        if msg == WM_NCMOUSEMOVE || msg == WM_MOUSEMOVE {
+            let pointer_type = pointer::from_message_extra_info(GetMessageExtraInfo().0 as u32);
+            data.pointer_type
+                .set(pointer_type.unwrap_or(PointerType::Mouse));
+        }
+        if msg == WM_NCHITTEST && !dwm_handled {
+            let global_position = Point::from_l_param(l_param);
 
-                let def_hit_test = non_client_hit_test(global_position, &metrics, options);
-                let hit_test = transform_hit_test(def_hit_test, options);
-                if !matches!(hit_test, HitTestArea::Client) {
-                    return hit_test.l_result();
+            let hit_test = hit_test_area(h_wnd, global_position, data);
+            if matches!(hit_test, HitTestArea::Caption)
+                && options.caption_drag == CaptionDrag::AfterThreshold
+            {
+                // Dragging is started by hand once the mouse moves far enough.
+                return LRESULT(HTCLIENT);
+            }
+            if !matches!(hit_test, HitTestArea::Client) {
+                return hit_test.l_result();
+            }
+        }
+        if msg == WM_NCMOUSEMOVE && !dwm_handled {
+            let over = CaptionButton::from_hit_test_code(w_param.0 as i32);
+            if !data.caption_buttons.get().is_pressed() {
+                data.update_caption_buttons(h_wnd, |buttons| buttons.pointer_moved(over));
+            }
+            if over.is_some() {
+                track_non_client_leave(h_wnd);
+            }
+        }
+        if msg == WM_NCMOUSELEAVE && !data.caption_buttons.get().is_pressed() {
+            data.update_caption_buttons(h_wnd, |buttons| buttons.pointer_left());
+        }
+        if (msg == WM_NCLBUTTONDOWN || msg == WM_NCLBUTTONDBLCLK) && !dwm_handled {
+            if let Some(button) = CaptionButton::from_hit_test_code(w_param.0 as i32) {
+                // Track the press ourselves instead of the default caption button loop.
+                data.update_caption_buttons(h_wnd, |buttons| buttons.pointer_pressed(Some(button)));
+                SetCapture(h_wnd);
+                return LRESULT(0);
+            }
+        }
+        if data.caption_buttons.get().is_pressed() {
+            if msg == WM_MOUSEMOVE || msg == WM_LBUTTONUP {
+                // The window has the mouse captured, so these are in client coordinates.
+                let mut global_position = Point::from_l_param(l_param).to_win32();
+                ClientToScreen(h_wnd, &mut global_position);
+                let over = match hit_test_area(h_wnd, Point::from_win32(&global_position), data) {
+                    HitTestArea::CaptionButton(button) => Some(button),
+                    _ => None,
+                };
+                if msg == WM_MOUSEMOVE {
+                    data.update_caption_buttons(h_wnd, |buttons| buttons.pointer_moved(over));
+                } else {
+                    data.update_caption_buttons(h_wnd, |buttons| buttons.pointer_released(over));
+                    ReleaseCapture();
                 }
+                return LRESULT(0);
             }
-
-            if dwm_handled {
-                return dwm_result;
+            if msg == WM_CAPTURECHANGED {
+                data.update_caption_buttons(h_wnd, |buttons| buttons.cancel());
+            }
+        }
+        if options.caption_drag == CaptionDrag::AfterThreshold && !fullscreen {
+            if msg == WM_LBUTTONDOWN {
+                if let Some(global_position) = caption_position(h_wnd, l_param, data) {
+                    data.caption_press.set(Some(global_position));
+                }
             }
+            if msg == WM_MOUSEMOVE {
+                if let Some(start) = data.caption_press.get() {
+                    let mut global_position = Point::from_l_param(l_param).to_win32();
+                    ClientToScreen(h_wnd, &mut global_position);
+                    let global_position = Point::from_win32(&global_position);
+                    let button_down = w_param.0 & MK_LBUTTON as usize != 0;
+                    match caption_press(start, global_position, button_down, drag_threshold()) {
+                        CaptionPress::Held => {}
+                        // The button went up somewhere we didn't see it.
+                        CaptionPress::Released => data.caption_press.set(None),
+                        CaptionPress::Dragged => {
+                            // Cleared first, a failed drag mustn't leave the press behind.
+                            data.caption_press.set(None);
+                            if drag::begin_drag_move(h_wnd).is_ok() {
+                                return LRESULT(0);
+                            }
+                        }
+                    }
+                }
+            }
+            if msg == WM_LBUTTONUP || msg == WM_CAPTURECHANGED {
+                // Just a click, it goes on to the app unchanged.
+                data.caption_press.set(None);
+            }
+            // The caption is client area here, so do what the native caption would, but only
+            // where the application's widgets can't be.
+            if msg == WM_LBUTTONDBLCLK {
+                if let Some(position) = declared_caption_position(h_wnd, l_param, data) {
+                    run_caption_action(h_wnd, &options.caption_double_click, position);
+                    return LRESULT(0);
+                }
+            }
+            if msg == WM_MBUTTONUP {
+                if let Some(action) = &options.caption_middle_click {
+                    if let Some(position) = declared_caption_position(h_wnd, l_param, data) {
+                        run_caption_action(h_wnd, action, position);
+                        return LRESULT(0);
+                    }
+                }
+            }
+            if msg == WM_RBUTTONUP {
+                if let Some(position) = declared_caption_position(h_wnd, l_param, data) {
+                    command::show_system_menu_at(h_wnd, position, &data.state.get());
+                    return LRESULT(0);
+                }
+            }
+        }
+        if msg == WM_NCLBUTTONDBLCLK && w_param.0 as i32 == HTCAPTION && !dwm_handled {
+            // Toggling maximize is what the system does already.
+            if !matches!(options.caption_double_click, CaptionAction::ToggleMaximize) {
+                let position = Point::from_l_param(l_param);
+                run_caption_action(h_wnd, &options.caption_double_click, position);
+                return LRESULT(0);
+            }
+        }
+        if msg == WM_NCMBUTTONUP && w_param.0 as i32 == HTCAPTION && !dwm_handled {
+            if let Some(action) = &options.caption_middle_click {
+                run_caption_action(h_wnd, action, Point::from_l_param(l_param));
+                return LRESULT(0);
+            }
+        }
+        if msg == WM_NCRBUTTONUP && w_param.0 as i32 == HTCAPTION && !dwm_handled {
+            command::show_system_menu_at(h_wnd, Point::from_l_param(l_param), &data.state.get());
+            return LRESULT(0);
+        }
+        if msg == WM_SYSCOMMAND
+            && (w_param.0 & 0xfff0) == SC_KEYMENU as usize
+            && l_param.0 == ' ' as isize
+            && options.extend_client_area.top > 0
+        {
+            // Alt+Space, open the menu just below the custom caption.
+            let mut position = Point {
+                x: 0,
+                y: options.extend_client_area.top,
+            }
+            .to_win32();
+            ClientToScreen(h_wnd, &mut position);
+            command::show_system_menu_at(h_wnd, Point::from_win32(&position), &data.state.get());
+            return LRESULT(0);
+        }
+        if msg == WM_GETOBJECT
+            && l_param.0 as i32 == UIA_ROOT_OBJECT_ID
+            && options.caption_button_layout.is_some()
+        {
+            // Expose the caption buttons we draw ourselves to assistive technologies.
+            data.update_accessibility(h_wnd, true);
+            if let Some(result) = return_root_provider(h_wnd, &data.accessibility, w_param, l_param)
+            {
+                return result;
+            }
+        }
+        if !dwm_enabled {
+            if msg == WM_NCPAINT {
+                paint_non_client_frame(h_wnd, state.active);
+                return LRESULT(0);
+            }
+            if msg == WM_NCACTIVATE {
+                // Keep the default activation handling, but stop it from painting the
+                // classic caption over the fallback frame.
+                let result = DefSubclassProc(h_wnd, u_msg, w_param, LPARAM(-1));
+                paint_non_client_frame(h_wnd, w_param.0 != 0);
+                return result;
+            }
+        }
+
+        if dwm_handled {
+            return dwm_result;
         }
 
         DefSubclassProc(h_wnd, u_msg, w_param, l_param)
@@ -0,0 +1,237 @@
+use crate::{
+    hit_test::{Point, Rect},
+    layout::CaptionButtonRect,
+    title_layout::free_span,
+};
+
+/// Tabs laid out in the caption, Chrome style, with room left over to drag the window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabStripLayout {
+    /// Preferred widths, one per tab.
+    pub tab_widths: Vec<i32>,
+    /// Tabs shrink down to this width when they don't all fit.
+    pub min_tab_width: i32,
+    pub tab_height: i32,
+    /// Distance from the top of the caption.
+    pub top: i32,
+    /// Draggable space before the first tab.
+    pub leading_gap: i32,
+    /// Draggable space between tabs.
+    pub tab_gap: i32,
+    /// Draggable space that's always kept free before the caption buttons.
+    pub trailing_gap: i32,
+}
+impl TabStripLayout {
+    pub fn new(tab_count: usize, tab_width: i32, tab_height: i32) -> Self {
+        Self {
+            tab_widths: vec![tab_width; tab_count],
+            min_tab_width: tab_width.min(48),
+            tab_height,
+            top: 0,
+            leading_gap: 0,
+            tab_gap: 0,
+            trailing_gap: 48,
+        }
+    }
+    /// Lays out the tabs in `caption`, in client coordinates, next to the caption buttons.
+    pub fn layout(&self, caption: Rect, buttons: &[CaptionButtonRect]) -> TabStrip {
+        let (left, right) = free_span(caption, buttons);
+        let top = caption.top + self.top;
+        let bounds = Rect {
+            left,
+            top,
+            right: right.max(left),
+            bottom: top + self.tab_height,
+        };
+        let tabs_left = left + self.leading_gap;
+        let tabs_right = right - self.trailing_gap;
+
+        let gaps = self.tab_gap * (self.tab_widths.len() as i32 - 1).max(0);
+        let available = (tabs_right - tabs_left - gaps).max(0);
+        let preferred: i32 = self.tab_widths.iter().sum();
+        let widths = self.tab_widths.iter().map(|width| {
+            if preferred > available {
+                (width * available / preferred).max(self.min_tab_width)
+            } else {
+                *width
+            }
+        });
+
+        let mut tabs = Vec::with_capacity(self.tab_widths.len());
+        let mut x = tabs_left;
+        for width in widths {
+            // Tabs that still don't fit are cut off at the end of the strip.
+            let right = (x + width).min(tabs_right);
+            if right <= x {
+                break;
+            }
+            tabs.push(Rect {
+                left: x,
+                right,
+                ..bounds
+            });
+            x = right + self.tab_gap;
+        }
+
+        let mut drag_regions = Vec::with_capacity(tabs.len() + 1);
+        let mut x = bounds.left;
+        for tab in &tabs {
+            if tab.left > x {
+                drag_regions.push(Rect {
+                    left: x,
+                    right: tab.left,
+                    ..bounds
+                });
+            }
+            x = tab.right;
+        }
+        if bounds.right > x {
+            drag_regions.push(Rect {
+                left: x,
+                right: bounds.right,
+                ..bounds
+            });
+        }
+
+        TabStrip {
+            bounds,
+            tabs,
+            drag_regions,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabStripArea {
+    Tab(usize),
+    /// Empty space in the strip, which moves the window.
+    Drag,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TabStrip {
+    /// The whole strip, between the caption buttons.
+    pub bounds: Rect,
+    pub tabs: Vec<Rect>,
+    pub drag_regions: Vec<Rect>,
+}
+impl TabStrip {
+    pub fn hit_test(&self, point: Point) -> Option<TabStripArea> {
+        if let Some(index) = self.tabs.iter().position(|tab| tab.contains(point)) {
+            Some(TabStripArea::Tab(index))
+        } else if self.bounds.contains(point) {
+            Some(TabStripArea::Drag)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::caption_buttons::CaptionButton};
+
+    fn area(left: i32, right: i32) -> Rect {
+        Rect {
+            left,
+            top: 0,
+            right,
+            bottom: 32,
+        }
+    }
+
+    fn tab(left: i32, right: i32) -> Rect {
+        Rect {
+            left,
+            top: 0,
+            right,
+            bottom: 28,
+        }
+    }
+
+    fn close_button(left: i32, right: i32) -> CaptionButtonRect {
+        CaptionButtonRect {
+            button: CaptionButton::Close,
+            rect: area(left, right),
+        }
+    }
+
+    fn spaced_layout() -> TabStripLayout {
+        TabStripLayout {
+            leading_gap: 10,
+            tab_gap: 4,
+            ..TabStripLayout::new(2, 100, 28)
+        }
+    }
+
+    #[test]
+    fn tabs_keep_their_width_when_they_fit() {
+        let strip = spaced_layout().layout(area(0, 400), &[close_button(340, 400)]);
+        assert_eq!(strip.bounds, tab(0, 340));
+        assert_eq!(strip.tabs, vec![tab(10, 110), tab(114, 214)]);
+        assert_eq!(
+            strip.drag_regions,
+            vec![tab(0, 10), tab(110, 114), tab(214, 340)]
+        );
+    }
+
+    #[test]
+    fn tabs_shrink_to_fit() {
+        let strip = TabStripLayout::new(4, 100, 28).layout(area(0, 300), &[]);
+        assert_eq!(
+            strip.tabs,
+            vec![tab(0, 63), tab(63, 126), tab(126, 189), tab(189, 252)]
+        );
+        assert_eq!(strip.drag_regions, vec![tab(252, 300)]);
+    }
+
+    #[test]
+    fn tabs_below_the_minimum_width_are_cut_off() {
+        let strip = TabStripLayout::new(4, 100, 28).layout(area(0, 150), &[]);
+        assert_eq!(strip.tabs, vec![tab(0, 48), tab(48, 96), tab(96, 102)]);
+        assert_eq!(strip.drag_regions, vec![tab(102, 150)]);
+    }
+
+    #[test]
+    fn a_strip_without_room_for_tabs_still_drags() {
+        let strip = TabStripLayout::new(1, 100, 28).layout(area(0, 40), &[]);
+        assert!(strip.tabs.is_empty());
+        assert_eq!(strip.drag_regions, vec![tab(0, 40)]);
+    }
+
+    #[test]
+    fn top_offsets_the_strip() {
+        let layout = TabStripLayout {
+            top: 4,
+            ..TabStripLayout::new(1, 100, 28)
+        };
+        let strip = layout.layout(area(0, 400), &[]);
+        assert_eq!(
+            strip.bounds,
+            Rect {
+                left: 0,
+                top: 4,
+                right: 400,
+                bottom: 32,
+            }
+        );
+        assert_eq!(strip.hit_test(Point { x: 50, y: 2 }), None);
+        assert_eq!(
+            strip.hit_test(Point { x: 50, y: 4 }),
+            Some(TabStripArea::Tab(0))
+        );
+    }
+
+    #[test]
+    fn hit_test_tells_tabs_from_gaps() {
+        let strip = spaced_layout().layout(area(0, 400), &[close_button(340, 400)]);
+        let hit_test = |x, y| strip.hit_test(Point { x, y });
+        assert_eq!(hit_test(5, 5), Some(TabStripArea::Drag));
+        assert_eq!(hit_test(50, 5), Some(TabStripArea::Tab(0)));
+        assert_eq!(hit_test(112, 5), Some(TabStripArea::Drag));
+        assert_eq!(hit_test(150, 27), Some(TabStripArea::Tab(1)));
+        assert_eq!(hit_test(300, 5), Some(TabStripArea::Drag));
+        assert_eq!(hit_test(150, 28), None);
+        assert_eq!(hit_test(350, 5), None);
+    }
+}
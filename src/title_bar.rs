@@ -0,0 +1,211 @@
+use crate::{
+    accessibility::AccessibilityTree,
+    caption_buttons::CaptionButtons,
+    dark_mode::Theme,
+    glyphs::{blend, draw_caption_button, fill_rect, GlyphColors, PixelFormat},
+    hit_test::Rect,
+    layout::{CaptionButtonRect, CaptionMetrics},
+    options::WindowFrame,
+    title_layout::{free_span, layout_title, MeasureTitleCallback, TitleAlignment},
+};
+
+// Spacing at 96 DPI, matching the system caption.
+const ICON_SIZE: f32 = 16.0;
+const PADDING: f32 = 8.0;
+
+/// Draws `title` into the rect of an ARGB pixel buffer, in the given color.
+pub type DrawTitleCallback = Box<dyn Fn(&mut [u32], usize, Rect, &str, u32)>;
+
+/// Colors in `0xAARRGGBB` format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TitleBarColors {
+    pub background: u32,
+    pub foreground: u32,
+    pub inactive_background: u32,
+    pub inactive_foreground: u32,
+}
+impl TitleBarColors {
+    pub fn for_theme(theme: &Theme) -> Self {
+        match theme {
+            Theme::Light => Self {
+                background: 0xffff_ffff,
+                foreground: 0xff00_0000,
+                inactive_background: 0xffff_ffff,
+                inactive_foreground: 0xff99_9999,
+            },
+            Theme::Dark => Self {
+                background: 0xff20_2020,
+                foreground: 0xffff_ffff,
+                inactive_background: 0xff2b_2b2b,
+                inactive_foreground: 0xff80_8080,
+            },
+        }
+    }
+}
+
+/// Straight alpha ARGB pixels, scaled to the caption's icon size when drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleBarIcon {
+    pub pixels: Vec<u32>,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// What to paint, usually from `WindowCustomization::title_bar`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TitleBar<'a> {
+    pub title: &'a str,
+    /// The caption area in client coordinates.
+    pub caption: Rect,
+    pub active: bool,
+    pub scale: f32,
+    pub buttons: Vec<CaptionButtonRect>,
+    pub button_states: CaptionButtons,
+}
+impl<'a> TitleBar<'a> {
+    pub fn accessibility_tree(&self) -> AccessibilityTree {
+        AccessibilityTree::new(self.title, self.caption, &self.buttons, &self.button_states)
+    }
+}
+
+/// Paints a complete title bar into the part of a pixel buffer covered by
+/// `WindowFrame::extend_client_area`.
+pub struct TitleBarRenderer {
+    pub colors: TitleBarColors,
+    pub button_colors: GlyphColors,
+    pub icon: Option<TitleBarIcon>,
+    /// The title is left out when there's no way to draw text.
+    pub draw_title: Option<DrawTitleCallback>,
+    /// Lets the title be aligned and cut short with an ellipsis before it's drawn.
+    pub measure_title: Option<MeasureTitleCallback>,
+    pub title_alignment: TitleAlignment,
+}
+impl TitleBarRenderer {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            colors: TitleBarColors::for_theme(&theme),
+            button_colors: GlyphColors::for_theme(&theme),
+            icon: None,
+            draw_title: None,
+            measure_title: None,
+            title_alignment: TitleAlignment::Left,
+        }
+    }
+    pub fn render(&self, buffer: &mut [u32], buffer_width: usize, title_bar: &TitleBar) {
+        let (background, foreground) = if title_bar.active {
+            (self.colors.background, self.colors.foreground)
+        } else {
+            (
+                self.colors.inactive_background,
+                self.colors.inactive_foreground,
+            )
+        };
+        fill_rect(
+            buffer,
+            buffer_width,
+            PixelFormat::Argb,
+            title_bar.caption,
+            background,
+        );
+
+        let padding = (PADDING * title_bar.scale).round() as i32;
+        let (mut left, right) = free_span(title_bar.caption, &title_bar.buttons);
+        left += padding;
+
+        if let Some(icon) = &self.icon {
+            let size = (ICON_SIZE * title_bar.scale).round() as i32;
+            let top = title_bar.caption.top + (title_bar.caption.height() - size) / 2;
+            let rect = Rect {
+                left,
+                top,
+                right: left + size,
+                bottom: top + size,
+            };
+            draw_icon(buffer, buffer_width, rect, icon);
+            left = rect.right + padding;
+        }
+
+        if let Some(draw_title) = &self.draw_title {
+            let rect = Rect {
+                left,
+                right: right - padding,
+                ..title_bar.caption
+            };
+            match &self.measure_title {
+                Some(measure) => {
+                    let layout = layout_title(
+                        title_bar.title,
+                        rect,
+                        &title_bar.buttons,
+                        self.title_alignment,
+                        measure,
+                    );
+                    if let Some(first) = layout.glyphs.first() {
+                        let left = first.x.round() as i32;
+                        let rect = Rect {
+                            left,
+                            right: left + layout.width().ceil() as i32,
+                            ..rect
+                        };
+                        draw_title(buffer, buffer_width, rect, &layout.text, foreground);
+                    }
+                }
+                None if rect.width() > 0 => {
+                    draw_title(buffer, buffer_width, rect, title_bar.title, foreground);
+                }
+                None => {}
+            }
+        }
+
+        let button_colors = GlyphColors {
+            foreground,
+            ..self.button_colors
+        };
+        for button in &title_bar.buttons {
+            draw_caption_button(
+                buffer,
+                buffer_width,
+                PixelFormat::Argb,
+                button.rect,
+                title_bar.button_states.glyph(button.button),
+                title_bar.button_states.state(button.button),
+                title_bar.scale,
+                &button_colors,
+            );
+        }
+    }
+}
+
+/// The part of the client area covered by `WindowFrame::extend_client_area`, minus what
+/// a maximized window hides off screen.
+pub(crate) fn caption_rect(
+    client_width: i32,
+    options: &WindowFrame,
+    metrics: &CaptionMetrics,
+    maximized: bool,
+) -> Rect {
+    Rect {
+        left: 0,
+        top: if maximized { metrics.maximized_top } else { 0 },
+        right: client_width,
+        bottom: options.extend_client_area.top,
+    }
+}
+
+// Nearest neighbour scaling is enough for the small icon.
+fn draw_icon(buffer: &mut [u32], buffer_width: usize, rect: Rect, icon: &TitleBarIcon) {
+    if buffer_width == 0 || icon.width == 0 || icon.height == 0 {
+        return;
+    }
+    let buffer_height = (buffer.len() / buffer_width) as i32;
+    for y in rect.top.max(0)..rect.bottom.min(buffer_height) {
+        for x in rect.left.max(0)..rect.right.min(buffer_width as i32) {
+            let icon_x = (x - rect.left) as usize * icon.width / rect.width() as usize;
+            let icon_y = (y - rect.top) as usize * icon.height / rect.height() as usize;
+            if let Some(color) = icon.pixels.get(icon_y * icon.width + icon_x) {
+                let pixel = &mut buffer[y as usize * buffer_width + x as usize];
+                *pixel = blend(*pixel, *color, 1.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{
+    hit_test::Rect,
+    layout::{CaptionButtonRect, CaptionStyle},
+};
+
+const ELLIPSIS: char = '\u{2026}';
+
+/// Returns the advance width of a character in pixels.
+pub type MeasureTitleCallback = Box<dyn Fn(char) -> f32>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleAlignment {
+    Left,
+    /// Centered within the space left over by the caption buttons.
+    Center,
+}
+impl TitleAlignment {
+    pub fn for_style(style: CaptionStyle) -> Self {
+        match style {
+            CaptionStyle::Windows10 => Self::Left,
+            CaptionStyle::Windows11 => Self::Center,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TitleGlyph {
+    pub character: char,
+    /// Left edge of the glyph in client coordinates.
+    pub x: f32,
+    pub advance: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TitleLayout {
+    /// The title as it should be shown, ending in an ellipsis if it was cut short.
+    pub text: String,
+    pub truncated: bool,
+    pub glyphs: Vec<TitleGlyph>,
+}
+impl TitleLayout {
+    pub fn width(&self) -> f32 {
+        self.glyphs.iter().map(|glyph| glyph.advance).sum()
+    }
+}
+
+/// Lays out the title on one line between the caption buttons, cutting it short with an
+/// ellipsis when it doesn't fit in `area`.
+pub fn layout_title(
+    title: &str,
+    area: Rect,
+    buttons: &[CaptionButtonRect],
+    alignment: TitleAlignment,
+    measure: impl Fn(char) -> f32,
+) -> TitleLayout {
+    let (left, right) = free_span(area, buttons);
+    let available = (right - left) as f32;
+    if available <= 0.0 {
+        return TitleLayout::default();
+    }
+
+    let mut characters: Vec<(char, f32)> = title.chars().map(|c| (c, measure(c))).collect();
+    let total: f32 = characters.iter().map(|(_, advance)| advance).sum();
+    let truncated = total > available;
+    if truncated {
+        let ellipsis = measure(ELLIPSIS);
+        let mut width = 0.0;
+        let fits = characters
+            .iter()
+            .take_while(|(_, advance)| {
+                width += advance;
+                width + ellipsis <= available
+            })
+            .count();
+        characters.truncate(fits);
+        while characters.last().map(|(c, _)| c.is_whitespace()) == Some(true) {
+            characters.pop();
+        }
+        if ellipsis <= available {
+            characters.push((ELLIPSIS, ellipsis));
+        }
+    }
+
+    let width: f32 = characters.iter().map(|(_, advance)| advance).sum();
+    let mut x = match alignment {
+        TitleAlignment::Left => left as f32,
+        TitleAlignment::Center => left as f32 + (available - width) / 2.0,
+    };
+    let glyphs = characters
+        .iter()
+        .map(|&(character, advance)| {
+            let glyph = TitleGlyph {
+                character,
+                x,
+                advance,
+            };
+            x += advance;
+            glyph
+        })
+        .collect();
+    TitleLayout {
+        text: characters.iter().map(|(c, _)| c).collect(),
+        truncated,
+        glyphs,
+    }
+}
+
+/// The horizontal span of the caption that isn't taken up by buttons.
+pub(crate) fn free_span(caption: Rect, buttons: &[CaptionButtonRect]) -> (i32, i32) {
+    let center = (caption.left + caption.right) / 2;
+    let left = buttons
+        .iter()
+        .filter(|button| button.rect.right <= center)
+        .map(|button| button.rect.right)
+        .fold(caption.left, i32::max);
+    let right = buttons
+        .iter()
+        .filter(|button| button.rect.left > center)
+        .map(|button| button.rect.left)
+        .fold(caption.right, i32::min);
+    (left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::caption_buttons::CaptionButton};
+
+    fn area(left: i32, right: i32) -> Rect {
+        Rect {
+            left,
+            top: 0,
+            right,
+            bottom: 32,
+        }
+    }
+
+    fn button(left: i32, right: i32) -> CaptionButtonRect {
+        CaptionButtonRect {
+            button: CaptionButton::Close,
+            rect: area(left, right),
+        }
+    }
+
+    fn monospace(_: char) -> f32 {
+        10.0
+    }
+
+    #[test]
+    fn short_titles_are_left_aligned_or_centered() {
+        let layout = layout_title("Hello", area(0, 200), &[], TitleAlignment::Left, monospace);
+        assert_eq!(layout.text, "Hello");
+        assert!(!layout.truncated);
+        assert_eq!(layout.glyphs[0].x, 0.0);
+        assert_eq!(layout.glyphs[4].x, 40.0);
+        assert_eq!(layout.width(), 50.0);
+
+        let layout = layout_title(
+            "Hello",
+            area(0, 200),
+            &[],
+            TitleAlignment::Center,
+            monospace,
+        );
+        assert_eq!(layout.glyphs[0].x, 75.0);
+    }
+
+    #[test]
+    fn centering_leaves_out_the_buttons() {
+        let buttons = [button(0, 30), button(170, 200)];
+        let layout = layout_title(
+            "Hello",
+            area(0, 200),
+            &buttons,
+            TitleAlignment::Center,
+            monospace,
+        );
+        assert_eq!(layout.glyphs[0].x, 75.0);
+
+        let layout = layout_title(
+            "Hello",
+            area(0, 200),
+            &buttons[1..],
+            TitleAlignment::Center,
+            monospace,
+        );
+        assert_eq!(layout.glyphs[0].x, 60.0);
+    }
+
+    #[test]
+    fn long_titles_end_in_an_ellipsis() {
+        let layout = layout_title(
+            "Hello world",
+            area(0, 60),
+            &[],
+            TitleAlignment::Left,
+            monospace,
+        );
+        assert_eq!(layout.text, "Hello\u{2026}");
+        assert!(layout.truncated);
+        assert_eq!(layout.width(), 60.0);
+    }
+
+    #[test]
+    fn whitespace_before_the_ellipsis_is_trimmed() {
+        let layout = layout_title(
+            "Hello world",
+            area(0, 70),
+            &[],
+            TitleAlignment::Left,
+            monospace,
+        );
+        assert_eq!(layout.text, "Hello\u{2026}");
+    }
+
+    #[test]
+    fn nothing_fits_without_space() {
+        let layout = layout_title("Hello", area(0, 5), &[], TitleAlignment::Left, monospace);
+        assert_eq!(layout.text, "");
+        assert!(layout.truncated);
+
+        let layout = layout_title("Hello", area(10, 10), &[], TitleAlignment::Left, monospace);
+        assert_eq!(layout, TitleLayout::default());
+    }
+
+    #[test]
+    fn free_span_sits_between_the_button_groups() {
+        assert_eq!(free_span(area(0, 500), &[]), (0, 500));
+        assert_eq!(
+            free_span(
+                area(0, 500),
+                &[button(0, 40), button(365, 410), button(410, 455)]
+            ),
+            (40, 365)
+        );
+    }
+}
@@ -0,0 +1,630 @@
+use {
+    crate::{
+        accessibility::{AccessibilityTree, AccessibleRole},
+        bindings::windows::win32::{
+            display_devices::POINT,
+            gdi::{ClientToScreen, ScreenToClient},
+            system_services::LRESULT,
+            windows_and_messaging::{HWND, LPARAM, WPARAM},
+        },
+        command,
+        hit_test::Point,
+    },
+    std::{
+        ffi::c_void,
+        ptr,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc, Mutex,
+        },
+    },
+    windows_dll::dll,
+};
+
+// UI Automation is plain COM, the providers below are laid out by hand.
+type HRESULT = i32;
+
+const S_OK: HRESULT = 0;
+const E_NOINTERFACE: HRESULT = 0x8000_4002_u32 as i32;
+const E_INVALIDARG: HRESULT = 0x8007_0057_u32 as i32;
+const UIA_E_ELEMENTNOTAVAILABLE: HRESULT = 0x8004_0201_u32 as i32;
+
+pub(crate) const UIA_ROOT_OBJECT_ID: i32 = -25;
+
+const PROVIDER_OPTIONS_SERVER_SIDE_PROVIDER: i32 = 0x1;
+
+const NAVIGATE_PARENT: i32 = 0;
+const NAVIGATE_NEXT_SIBLING: i32 = 1;
+const NAVIGATE_PREVIOUS_SIBLING: i32 = 2;
+const NAVIGATE_FIRST_CHILD: i32 = 3;
+const NAVIGATE_LAST_CHILD: i32 = 4;
+
+const UIA_INVOKE_PATTERN_ID: i32 = 10000;
+const UIA_CONTROL_TYPE_PROPERTY_ID: i32 = 30003;
+const UIA_NAME_PROPERTY_ID: i32 = 30005;
+const UIA_AUTOMATION_ID_PROPERTY_ID: i32 = 30011;
+const UIA_BUTTON_CONTROL_TYPE_ID: i32 = 50000;
+const UIA_TITLE_BAR_CONTROL_TYPE_ID: i32 = 50037;
+const UIA_APPEND_RUNTIME_ID: i32 = 3;
+
+const VT_EMPTY: u16 = 0;
+const VT_I4: u16 = 3;
+const VT_BSTR: u16 = 8;
+
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Guid(u32, u16, u16, [u8; 8]);
+
+const IID_IUNKNOWN: Guid = Guid(0, 0, 0, [0xc0, 0, 0, 0, 0, 0, 0, 0x46]);
+const IID_RAW_ELEMENT_PROVIDER_SIMPLE: Guid = Guid(
+    0xd6dd_68d1,
+    0x86fd,
+    0x4332,
+    [0x86, 0x66, 0x9a, 0xbe, 0xde, 0xa2, 0xd2, 0x4c],
+);
+const IID_RAW_ELEMENT_PROVIDER_FRAGMENT: Guid = Guid(
+    0xf706_3da8,
+    0x8359,
+    0x439c,
+    [0x92, 0x97, 0xbb, 0xc5, 0x29, 0x9a, 0x7d, 0x87],
+);
+const IID_RAW_ELEMENT_PROVIDER_FRAGMENT_ROOT: Guid = Guid(
+    0x620c_e2a5,
+    0xab8f,
+    0x40a9,
+    [0x86, 0xcb, 0xde, 0x3c, 0x75, 0x59, 0x9b, 0x58],
+);
+const IID_INVOKE_PROVIDER: Guid = Guid(
+    0x54fc_b24b,
+    0xe18e,
+    0x47a2,
+    [0xb4, 0xd3, 0xec, 0xcb, 0xe7, 0x75, 0x99, 0xa2],
+);
+
+#[repr(C)]
+struct Variant {
+    vt: u16,
+    reserved: [u16; 3],
+    value: VariantValue,
+}
+#[repr(C)]
+union VariantValue {
+    int: i32,
+    string: *mut u16,
+    // The largest member, a record pointer and its interface.
+    _record: [usize; 2],
+}
+
+#[repr(C)]
+struct UiaRect {
+    left: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+}
+
+#[dll(UIAutomationCore)]
+extern "system" {
+    #[allow(non_snake_case)]
+    fn UiaReturnRawElementProvider(
+        h_wnd: HWND,
+        w_param: WPARAM,
+        l_param: LPARAM,
+        provider: *mut c_void,
+    ) -> LRESULT;
+    #[allow(non_snake_case)]
+    fn UiaHostProviderFromHwnd(h_wnd: HWND, provider: *mut *mut c_void) -> HRESULT;
+}
+
+#[dll(OleAut32)]
+extern "system" {
+    #[allow(non_snake_case)]
+    fn SysAllocString(string: *const u16) -> *mut u16;
+    #[allow(non_snake_case)]
+    fn SafeArrayCreateVector(vt: u16, lower_bound: i32, elements: u32) -> *mut c_void;
+    #[allow(non_snake_case)]
+    fn SafeArrayPutElement(
+        array: *mut c_void,
+        indices: *const i32,
+        value: *const c_void,
+    ) -> HRESULT;
+}
+
+/// A copy of the title bar's tree, kept up to date on the window's thread.
+pub(crate) type SharedTree = Arc<Mutex<Option<AccessibilityTree>>>;
+
+/// Answers `WM_GETOBJECT` with the title bar's fragment root.
+///
+/// UI Automation may call the providers on any thread, so they only ever read `tree` and
+/// never the window's own state.
+pub(crate) unsafe fn return_root_provider(
+    h_wnd: HWND,
+    tree: &SharedTree,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> Option<LRESULT> {
+    if !UiaReturnRawElementProvider::exists() {
+        return None;
+    }
+    let provider = Provider::create(h_wnd, tree.clone(), AccessibilityTree::ROOT);
+    let result = UiaReturnRawElementProvider(h_wnd, w_param, l_param, provider.cast());
+    Provider::release(&*provider);
+    Some(result)
+}
+
+#[repr(C)]
+struct Provider {
+    // Each interface is a pointer to its own vtable, at a fixed slot.
+    simple: *const SimpleVtbl,
+    fragment: *const FragmentVtbl,
+    fragment_root: *const FragmentRootVtbl,
+    invoke: *const InvokeVtbl,
+    references: AtomicU32,
+    // Only used for calls that are safe from any thread.
+    h_wnd: HWND,
+    tree: SharedTree,
+    node: usize,
+}
+
+const SIMPLE_SLOT: usize = 0;
+const FRAGMENT_SLOT: usize = 1;
+const FRAGMENT_ROOT_SLOT: usize = 2;
+const INVOKE_SLOT: usize = 3;
+
+impl Provider {
+    fn create(h_wnd: HWND, tree: SharedTree, node: usize) -> *mut Provider {
+        Box::into_raw(Box::new(Provider {
+            simple: &SIMPLE_VTBL,
+            fragment: &FRAGMENT_VTBL,
+            fragment_root: &FRAGMENT_ROOT_VTBL,
+            invoke: &INVOKE_VTBL,
+            references: AtomicU32::new(1),
+            h_wnd,
+            tree,
+            node,
+        }))
+    }
+    unsafe fn from_interface<'a>(this: *mut c_void, slot: usize) -> &'a Provider {
+        &*((this as *mut *const c_void).sub(slot) as *const Provider)
+    }
+    fn interface(&self, slot: usize) -> *mut c_void {
+        unsafe {
+            (self as *const Provider as *mut *const c_void)
+                .add(slot)
+                .cast()
+        }
+    }
+    fn add_ref(&self) -> u32 {
+        self.references.fetch_add(1, Ordering::Relaxed) + 1
+    }
+    unsafe fn release(&self) -> u32 {
+        let references = self.references.fetch_sub(1, Ordering::Release) - 1;
+        if references == 0 {
+            std::sync::atomic::fence(Ordering::Acquire);
+            drop(Box::from_raw(self as *const Provider as *mut Provider));
+        }
+        references
+    }
+    // A new reference to another node of the same window.
+    fn sibling(&self, node: usize, slot: usize) -> *mut c_void {
+        let provider = Provider::create(self.h_wnd, self.tree.clone(), node);
+        unsafe { (*provider).interface(slot) }
+    }
+    // The latest snapshot, as long as this node is still part of it.
+    fn tree(&self) -> Option<AccessibilityTree> {
+        let tree = self.tree.lock().ok()?.clone()?;
+        if self.node < tree.nodes.len() {
+            Some(tree)
+        } else {
+            None
+        }
+    }
+
+    unsafe fn query_interface(&self, iid: *const Guid, out: *mut *mut c_void) -> HRESULT {
+        if out.is_null() || iid.is_null() {
+            return E_INVALIDARG;
+        }
+        let iid = *iid;
+        let slot = if iid == IID_IUNKNOWN || iid == IID_RAW_ELEMENT_PROVIDER_SIMPLE {
+            Some(SIMPLE_SLOT)
+        } else if iid == IID_RAW_ELEMENT_PROVIDER_FRAGMENT {
+            Some(FRAGMENT_SLOT)
+        } else if iid == IID_RAW_ELEMENT_PROVIDER_FRAGMENT_ROOT
+            && self.node == AccessibilityTree::ROOT
+        {
+            Some(FRAGMENT_ROOT_SLOT)
+        } else if iid == IID_INVOKE_PROVIDER && self.is_invokable() {
+            Some(INVOKE_SLOT)
+        } else {
+            None
+        };
+        match slot {
+            Some(slot) => {
+                self.add_ref();
+                *out = self.interface(slot);
+                S_OK
+            }
+            None => {
+                *out = ptr::null_mut();
+                E_NOINTERFACE
+            }
+        }
+    }
+    fn is_invokable(&self) -> bool {
+        self.tree()
+            .and_then(|tree| tree.nodes[self.node].command)
+            .is_some()
+    }
+
+    unsafe fn provider_options(&self, out: *mut i32) -> HRESULT {
+        *out = PROVIDER_OPTIONS_SERVER_SIDE_PROVIDER;
+        S_OK
+    }
+    unsafe fn pattern_provider(&self, pattern: i32, out: *mut *mut c_void) -> HRESULT {
+        *out = ptr::null_mut();
+        if pattern == UIA_INVOKE_PATTERN_ID && self.is_invokable() {
+            self.add_ref();
+            *out = self.interface(INVOKE_SLOT);
+        }
+        S_OK
+    }
+    unsafe fn property_value(&self, property: i32, out: *mut Variant) -> HRESULT {
+        // Unsupported properties have to be reported as empty.
+        (*out).vt = VT_EMPTY;
+        let tree = match self.tree() {
+            Some(tree) => tree,
+            None => return UIA_E_ELEMENTNOTAVAILABLE,
+        };
+        let node = &tree.nodes[self.node];
+        // The window's own properties come from its host provider.
+        if node.role == AccessibleRole::Window {
+            return S_OK;
+        }
+        let out = &mut *out;
+        match property {
+            UIA_CONTROL_TYPE_PROPERTY_ID => {
+                out.vt = VT_I4;
+                out.value.int = match node.role {
+                    AccessibleRole::TitleBar => UIA_TITLE_BAR_CONTROL_TYPE_ID,
+                    _ => UIA_BUTTON_CONTROL_TYPE_ID,
+                };
+            }
+            UIA_NAME_PROPERTY_ID => {
+                out.vt = VT_BSTR;
+                out.value.string = bstr(&node.name);
+            }
+            UIA_AUTOMATION_ID_PROPERTY_ID => {
+                out.vt = VT_BSTR;
+                out.value.string = bstr(&node.automation_id);
+            }
+            _ => {}
+        }
+        S_OK
+    }
+    unsafe fn host_provider(&self, out: *mut *mut c_void) -> HRESULT {
+        *out = ptr::null_mut();
+        if self.node == AccessibilityTree::ROOT {
+            UiaHostProviderFromHwnd(self.h_wnd, out)
+        } else {
+            S_OK
+        }
+    }
+
+    unsafe fn navigate(&self, direction: i32, out: *mut *mut c_void) -> HRESULT {
+        *out = ptr::null_mut();
+        let tree = match self.tree() {
+            Some(tree) => tree,
+            None => return UIA_E_ELEMENTNOTAVAILABLE,
+        };
+        let node = match direction {
+            // The host provider takes care of the window's parent.
+            NAVIGATE_PARENT if self.node == AccessibilityTree::ROOT => None,
+            NAVIGATE_PARENT => tree.parent(self.node),
+            NAVIGATE_NEXT_SIBLING => tree.next_sibling(self.node),
+            NAVIGATE_PREVIOUS_SIBLING => tree.previous_sibling(self.node),
+            NAVIGATE_FIRST_CHILD => tree.first_child(self.node),
+            NAVIGATE_LAST_CHILD => tree.last_child(self.node),
+            _ => None,
+        };
+        if let Some(node) = node {
+            *out = self.sibling(node, FRAGMENT_SLOT);
+        }
+        S_OK
+    }
+    unsafe fn runtime_id(&self, out: *mut *mut c_void) -> HRESULT {
+        *out = ptr::null_mut();
+        if self.node == AccessibilityTree::ROOT {
+            return S_OK;
+        }
+        let array = SafeArrayCreateVector(VT_I4, 0, 2);
+        if array.is_null() {
+            return UIA_E_ELEMENTNOTAVAILABLE;
+        }
+        let values = [UIA_APPEND_RUNTIME_ID, self.node as i32];
+        for (index, value) in values.iter().enumerate() {
+            SafeArrayPutElement(array, &(index as i32), (value as *const i32).cast());
+        }
+        *out = array;
+        S_OK
+    }
+    unsafe fn bounding_rectangle(&self, out: *mut UiaRect) -> HRESULT {
+        let tree = match self.tree() {
+            Some(tree) => tree,
+            None => return UIA_E_ELEMENTNOTAVAILABLE,
+        };
+        *out = UiaRect {
+            left: 0.0,
+            top: 0.0,
+            width: 0.0,
+            height: 0.0,
+        };
+        if self.node != AccessibilityTree::ROOT {
+            let bounds = tree.nodes[self.node].bounds;
+            let mut origin = POINT {
+                x: bounds.left,
+                y: bounds.top,
+            };
+            ClientToScreen(self.h_wnd, &mut origin);
+            *out = UiaRect {
+                left: origin.x as f64,
+                top: origin.y as f64,
+                width: bounds.width() as f64,
+                height: bounds.height() as f64,
+            };
+        }
+        S_OK
+    }
+    unsafe fn fragment_root(&self, out: *mut *mut c_void) -> HRESULT {
+        *out = if self.node == AccessibilityTree::ROOT {
+            self.add_ref();
+            self.interface(FRAGMENT_ROOT_SLOT)
+        } else {
+            self.sibling(AccessibilityTree::ROOT, FRAGMENT_ROOT_SLOT)
+        };
+        S_OK
+    }
+    unsafe fn element_from_point(&self, x: f64, y: f64, out: *mut *mut c_void) -> HRESULT {
+        *out = ptr::null_mut();
+        let tree = match self.tree() {
+            Some(tree) => tree,
+            None => return UIA_E_ELEMENTNOTAVAILABLE,
+        };
+        let mut position = POINT {
+            x: x as i32,
+            y: y as i32,
+        };
+        ScreenToClient(self.h_wnd, &mut position);
+        if let Some(node) = tree.node_at(Point::from_win32(&position)) {
+            *out = self.sibling(node, FRAGMENT_SLOT);
+        }
+        S_OK
+    }
+    unsafe fn invoke(&self) -> HRESULT {
+        match self.tree().and_then(|tree| tree.nodes[self.node].command) {
+            Some(window_command) => {
+                command::post(self.h_wnd, window_command);
+                S_OK
+            }
+            None => UIA_E_ELEMENTNOTAVAILABLE,
+        }
+    }
+}
+
+unsafe fn bstr(string: &str) -> *mut u16 {
+    let wide: Vec<u16> = string.encode_utf16().chain(Some(0)).collect();
+    SysAllocString(wide.as_ptr())
+}
+
+type QueryInterface =
+    unsafe extern "system" fn(*mut c_void, *const Guid, *mut *mut c_void) -> HRESULT;
+type AddRef = unsafe extern "system" fn(*mut c_void) -> u32;
+type Release = unsafe extern "system" fn(*mut c_void) -> u32;
+
+#[repr(C)]
+struct SimpleVtbl {
+    query_interface: QueryInterface,
+    add_ref: AddRef,
+    release: Release,
+    provider_options: unsafe extern "system" fn(*mut c_void, *mut i32) -> HRESULT,
+    pattern_provider: unsafe extern "system" fn(*mut c_void, i32, *mut *mut c_void) -> HRESULT,
+    property_value: unsafe extern "system" fn(*mut c_void, i32, *mut Variant) -> HRESULT,
+    host_provider: unsafe extern "system" fn(*mut c_void, *mut *mut c_void) -> HRESULT,
+}
+
+#[repr(C)]
+struct FragmentVtbl {
+    query_interface: QueryInterface,
+    add_ref: AddRef,
+    release: Release,
+    navigate: unsafe extern "system" fn(*mut c_void, i32, *mut *mut c_void) -> HRESULT,
+    runtime_id: unsafe extern "system" fn(*mut c_void, *mut *mut c_void) -> HRESULT,
+    bounding_rectangle: unsafe extern "system" fn(*mut c_void, *mut UiaRect) -> HRESULT,
+    embedded_fragment_roots: unsafe extern "system" fn(*mut c_void, *mut *mut c_void) -> HRESULT,
+    set_focus: unsafe extern "system" fn(*mut c_void) -> HRESULT,
+    fragment_root: unsafe extern "system" fn(*mut c_void, *mut *mut c_void) -> HRESULT,
+}
+
+#[repr(C)]
+struct FragmentRootVtbl {
+    query_interface: QueryInterface,
+    add_ref: AddRef,
+    release: Release,
+    element_from_point:
+        unsafe extern "system" fn(*mut c_void, f64, f64, *mut *mut c_void) -> HRESULT,
+    focus: unsafe extern "system" fn(*mut c_void, *mut *mut c_void) -> HRESULT,
+}
+
+#[repr(C)]
+struct InvokeVtbl {
+    query_interface: QueryInterface,
+    add_ref: AddRef,
+    release: Release,
+    invoke: unsafe extern "system" fn(*mut c_void) -> HRESULT,
+}
+
+static SIMPLE_VTBL: SimpleVtbl = SimpleVtbl {
+    query_interface: simple_query_interface,
+    add_ref: simple_add_ref,
+    release: simple_release,
+    provider_options: simple_provider_options,
+    pattern_provider: simple_pattern_provider,
+    property_value: simple_property_value,
+    host_provider: simple_host_provider,
+};
+
+static FRAGMENT_VTBL: FragmentVtbl = FragmentVtbl {
+    query_interface: fragment_query_interface,
+    add_ref: fragment_add_ref,
+    release: fragment_release,
+    navigate: fragment_navigate,
+    runtime_id: fragment_runtime_id,
+    bounding_rectangle: fragment_bounding_rectangle,
+    embedded_fragment_roots: fragment_embedded_fragment_roots,
+    set_focus: fragment_set_focus,
+    fragment_root: fragment_fragment_root,
+};
+
+static FRAGMENT_ROOT_VTBL: FragmentRootVtbl = FragmentRootVtbl {
+    query_interface: fragment_root_query_interface,
+    add_ref: fragment_root_add_ref,
+    release: fragment_root_release,
+    element_from_point: fragment_root_element_from_point,
+    focus: fragment_root_focus,
+};
+
+static INVOKE_VTBL: InvokeVtbl = InvokeVtbl {
+    query_interface: invoke_query_interface,
+    add_ref: invoke_add_ref,
+    release: invoke_release,
+    invoke: invoke_invoke,
+};
+
+unsafe extern "system" fn simple_query_interface(
+    this: *mut c_void,
+    iid: *const Guid,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    Provider::from_interface(this, SIMPLE_SLOT).query_interface(iid, out)
+}
+unsafe extern "system" fn simple_add_ref(this: *mut c_void) -> u32 {
+    Provider::from_interface(this, SIMPLE_SLOT).add_ref()
+}
+unsafe extern "system" fn simple_release(this: *mut c_void) -> u32 {
+    Provider::from_interface(this, SIMPLE_SLOT).release()
+}
+unsafe extern "system" fn simple_provider_options(this: *mut c_void, out: *mut i32) -> HRESULT {
+    Provider::from_interface(this, SIMPLE_SLOT).provider_options(out)
+}
+unsafe extern "system" fn simple_pattern_provider(
+    this: *mut c_void,
+    pattern: i32,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    Provider::from_interface(this, SIMPLE_SLOT).pattern_provider(pattern, out)
+}
+unsafe extern "system" fn simple_property_value(
+    this: *mut c_void,
+    property: i32,
+    out: *mut Variant,
+) -> HRESULT {
+    Provider::from_interface(this, SIMPLE_SLOT).property_value(property, out)
+}
+unsafe extern "system" fn simple_host_provider(
+    this: *mut c_void,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    Provider::from_interface(this, SIMPLE_SLOT).host_provider(out)
+}
+
+unsafe extern "system" fn fragment_query_interface(
+    this: *mut c_void,
+    iid: *const Guid,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    Provider::from_interface(this, FRAGMENT_SLOT).query_interface(iid, out)
+}
+unsafe extern "system" fn fragment_add_ref(this: *mut c_void) -> u32 {
+    Provider::from_interface(this, FRAGMENT_SLOT).add_ref()
+}
+unsafe extern "system" fn fragment_release(this: *mut c_void) -> u32 {
+    Provider::from_interface(this, FRAGMENT_SLOT).release()
+}
+unsafe extern "system" fn fragment_navigate(
+    this: *mut c_void,
+    direction: i32,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    Provider::from_interface(this, FRAGMENT_SLOT).navigate(direction, out)
+}
+unsafe extern "system" fn fragment_runtime_id(this: *mut c_void, out: *mut *mut c_void) -> HRESULT {
+    Provider::from_interface(this, FRAGMENT_SLOT).runtime_id(out)
+}
+unsafe extern "system" fn fragment_bounding_rectangle(
+    this: *mut c_void,
+    out: *mut UiaRect,
+) -> HRESULT {
+    Provider::from_interface(this, FRAGMENT_SLOT).bounding_rectangle(out)
+}
+unsafe extern "system" fn fragment_embedded_fragment_roots(
+    _this: *mut c_void,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    *out = ptr::null_mut();
+    S_OK
+}
+unsafe extern "system" fn fragment_set_focus(_this: *mut c_void) -> HRESULT {
+    S_OK
+}
+unsafe extern "system" fn fragment_fragment_root(
+    this: *mut c_void,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    Provider::from_interface(this, FRAGMENT_SLOT).fragment_root(out)
+}
+
+unsafe extern "system" fn fragment_root_query_interface(
+    this: *mut c_void,
+    iid: *const Guid,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    Provider::from_interface(this, FRAGMENT_ROOT_SLOT).query_interface(iid, out)
+}
+unsafe extern "system" fn fragment_root_add_ref(this: *mut c_void) -> u32 {
+    Provider::from_interface(this, FRAGMENT_ROOT_SLOT).add_ref()
+}
+unsafe extern "system" fn fragment_root_release(this: *mut c_void) -> u32 {
+    Provider::from_interface(this, FRAGMENT_ROOT_SLOT).release()
+}
+unsafe extern "system" fn fragment_root_element_from_point(
+    this: *mut c_void,
+    x: f64,
+    y: f64,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    Provider::from_interface(this, FRAGMENT_ROOT_SLOT).element_from_point(x, y, out)
+}
+unsafe extern "system" fn fragment_root_focus(
+    _this: *mut c_void,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    // Caption buttons never take keyboard focus.
+    *out = ptr::null_mut();
+    S_OK
+}
+
+unsafe extern "system" fn invoke_query_interface(
+    this: *mut c_void,
+    iid: *const Guid,
+    out: *mut *mut c_void,
+) -> HRESULT {
+    Provider::from_interface(this, INVOKE_SLOT).query_interface(iid, out)
+}
+unsafe extern "system" fn invoke_add_ref(this: *mut c_void) -> u32 {
+    Provider::from_interface(this, INVOKE_SLOT).add_ref()
+}
+unsafe extern "system" fn invoke_release(this: *mut c_void) -> u32 {
+    Provider::from_interface(this, INVOKE_SLOT).release()
+}
+unsafe extern "system" fn invoke_invoke(this: *mut c_void) -> HRESULT {
+    Provider::from_interface(this, INVOKE_SLOT).invoke()
+}
@@ -1,21 +1,46 @@
 use {
-    crate::bindings::windows::win32::{
-        display_devices::RECT,
-        dwm::DwmIsCompositionEnabled,
-        system_services::{FALSE, TRUE, WS_CAPTION, WS_OVERLAPPEDWINDOW},
-        windows_and_messaging::{AdjustWindowRectEx, WINDOWPOS_abi, HWND},
+    crate::{
+        bindings::windows::win32::{
+            display_devices::RECT,
+            dwm::DwmIsCompositionEnabled,
+            gdi::{GetMonitorInfoW, MonitorFromWindow, MONITORINFO},
+            system_services::{
+                FALSE, MONITOR_DEFAULTTONEAREST, SWP_FRAMECHANGED, TRUE, WM_GETTEXT,
+                WM_GETTEXTLENGTH, WS_CAPTION, WS_OVERLAPPEDWINDOW,
+            },
+            windows_and_messaging::{
+                AdjustWindowRectEx, GetClientRect, GetWindowRect, SendMessageW, SetWindowPos,
+                WINDOWPOS_abi, HWND, LPARAM, WPARAM,
+            },
+        },
+        hit_test::{Rect, Size},
+        options::Margins,
     },
-    raw_window_handle::{HasRawWindowHandle, RawWindowHandle},
+    std::{ffi::c_void, mem},
+    windows::BOOL,
+    windows_dll::dll,
 };
 
-pub(crate) fn windows_window_handle<W: HasRawWindowHandle>(window: &W) -> HWND {
-    // Get the window handle
-    let window_handle = window.raw_window_handle();
-    let window_handle = match window_handle {
-        RawWindowHandle::Windows(window_handle) => window_handle.hwnd,
-        _ => panic!("Unsupported platform!"),
-    };
-    HWND(window_handle as isize)
+const DWMWA_EXTENDED_FRAME_BOUNDS: u32 = 9;
+
+#[dll(dwmapi)]
+extern "system" {
+    #[allow(non_snake_case)]
+    fn DwmGetWindowAttribute(hwnd: HWND, attribute: u32, value: *mut c_void, size: u32) -> i32;
+}
+
+#[dll(user32)]
+extern "system" {
+    #[allow(non_snake_case)]
+    fn GetDpiForWindow(hwnd: HWND) -> u32;
+    #[allow(non_snake_case)]
+    fn AdjustWindowRectExForDpi(
+        rect: *mut RECT,
+        style: u32,
+        menu: BOOL,
+        ex_style: u32,
+        dpi: u32,
+    ) -> BOOL;
 }
 
 pub(crate) unsafe fn is_dwm_enabled() -> bool {
@@ -42,3 +67,110 @@ pub(crate) unsafe fn window_frame_borders(with_caption: bool) -> RECT {
     AdjustWindowRectEx(&mut rect, style_flags, false.into(), 0);
     rect
 }
+
+// Only known on Windows 10 1607 and later.
+pub(crate) unsafe fn window_dpi(h_wnd: HWND) -> Option<u32> {
+    if !GetDpiForWindow::exists() {
+        return None;
+    }
+    match GetDpiForWindow(h_wnd) {
+        0 => None,
+        dpi => Some(dpi),
+    }
+}
+
+pub(crate) unsafe fn caption_height_for_dpi(dpi: u32) -> Option<i32> {
+    if !AdjustWindowRectExForDpi::exists() {
+        return None;
+    }
+    let mut rect = RECT::default();
+    if !AdjustWindowRectExForDpi(&mut rect, WS_OVERLAPPEDWINDOW as _, false.into(), 0, dpi)
+        .as_bool()
+    {
+        return None;
+    }
+    Some(-rect.top)
+}
+
+pub(crate) unsafe fn frame_changed(h_wnd: HWND) {
+    let mut rect = RECT::default();
+    GetWindowRect(h_wnd, &mut rect);
+
+    // Inform application of the frame change.
+    let width = rect.right - rect.left;
+    let height = rect.bottom - rect.top;
+
+    SetWindowPos(
+        h_wnd,
+        HWND(0),
+        rect.left,
+        rect.top,
+        width,
+        height,
+        SWP_FRAMECHANGED as _,
+    );
+}
+
+pub(crate) unsafe fn monitor_info(h_wnd: HWND) -> Option<MONITORINFO> {
+    let monitor = MonitorFromWindow(h_wnd, MONITOR_DEFAULTTONEAREST as _);
+    let mut monitor_info = MONITORINFO {
+        cb_size: std::mem::size_of::<MONITORINFO>() as _,
+        ..Default::default()
+    };
+    if GetMonitorInfoW(monitor, &mut monitor_info).as_bool() {
+        Some(monitor_info)
+    } else {
+        None
+    }
+}
+
+pub(crate) unsafe fn client_size(h_wnd: HWND) -> Size {
+    let mut rect = RECT::default();
+    GetClientRect(h_wnd, &mut rect);
+    Size {
+        width: rect.right - rect.left,
+        height: rect.bottom - rect.top,
+    }
+}
+
+// The window rectangle without the invisible resize borders DWM puts around it.
+pub(crate) unsafe fn visible_window_rect(h_wnd: HWND) -> Rect {
+    let mut rect = RECT::default();
+    if DwmGetWindowAttribute::exists()
+        && DwmGetWindowAttribute(
+            h_wnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rect as *mut RECT as *mut c_void,
+            mem::size_of::<RECT>() as _,
+        ) >= 0
+    {
+        return Rect::from_win32(&rect);
+    }
+    GetWindowRect(h_wnd, &mut rect);
+    Rect::from_win32(&rect)
+}
+
+pub(crate) unsafe fn invisible_borders(h_wnd: HWND) -> Margins {
+    let mut window = RECT::default();
+    GetWindowRect(h_wnd, &mut window);
+    let visible = visible_window_rect(h_wnd);
+    Margins {
+        left: (visible.left - window.left).max(0),
+        top: (visible.top - window.top).max(0),
+        right: (window.right - visible.right).max(0),
+        bottom: (window.bottom - visible.bottom).max(0),
+    }
+}
+
+pub(crate) unsafe fn window_text(h_wnd: HWND) -> String {
+    let length = SendMessageW(h_wnd, WM_GETTEXTLENGTH as _, WPARAM(0), LPARAM(0)).0;
+    let mut text = vec![0u16; length.max(0) as usize + 1];
+    let copied = SendMessageW(
+        h_wnd,
+        WM_GETTEXT as _,
+        WPARAM(text.len()),
+        LPARAM(text.as_mut_ptr() as _),
+    )
+    .0;
+    String::from_utf16_lossy(&text[..(copied.max(0) as usize).min(length.max(0) as usize)])
+}
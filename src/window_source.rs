@@ -0,0 +1,62 @@
+/// Anything that can hand out the win32 handle of a top-level window.
+///
+/// The `Marker` parameter only exists so that windows from several `raw-window-handle`
+/// generations can be supported at once; it is inferred at the call site. A window that
+/// implements several enabled generations needs it spelled out, e.g.
+/// `WindowCustomization::new::<Rwh06, _>(&window, options)`.
+pub trait WindowSource<Marker = ()> {
+    fn hwnd(&self) -> isize;
+}
+
+/// A bare win32 window handle, for windows that don't come from a
+/// `raw-window-handle` compatible library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawHwnd(pub isize);
+impl WindowSource for RawHwnd {
+    fn hwnd(&self) -> isize {
+        self.0
+    }
+}
+
+#[cfg(feature = "rwh_03")]
+pub enum Rwh03 {}
+#[cfg(feature = "rwh_03")]
+impl<W: rwh_03::HasRawWindowHandle> WindowSource<Rwh03> for W {
+    fn hwnd(&self) -> isize {
+        use rwh_03::RawWindowHandle;
+
+        match self.raw_window_handle() {
+            RawWindowHandle::Windows(window_handle) => window_handle.hwnd as isize,
+            _ => panic!("Unsupported platform!"),
+        }
+    }
+}
+
+#[cfg(feature = "rwh_05")]
+pub enum Rwh05 {}
+#[cfg(feature = "rwh_05")]
+impl<W: rwh_05::HasRawWindowHandle> WindowSource<Rwh05> for W {
+    fn hwnd(&self) -> isize {
+        use rwh_05::RawWindowHandle;
+
+        match self.raw_window_handle() {
+            RawWindowHandle::Win32(window_handle) => window_handle.hwnd as isize,
+            _ => panic!("Unsupported platform!"),
+        }
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+pub enum Rwh06 {}
+#[cfg(feature = "rwh_06")]
+impl<W: rwh_06::HasWindowHandle> WindowSource<Rwh06> for W {
+    fn hwnd(&self) -> isize {
+        use rwh_06::RawWindowHandle;
+
+        let window_handle = self.window_handle().expect("Window handle is unavailable!");
+        match window_handle.as_raw() {
+            RawWindowHandle::Win32(window_handle) => window_handle.hwnd.get(),
+            _ => panic!("Unsupported platform!"),
+        }
+    }
+}